@@ -2,11 +2,13 @@ use chrono::{offset::Local, DateTime, NaiveDate};
 use clap::{Parser, Subcommand, ValueEnum};
 use futures::StreamExt;
 use std::{
-    io,
+    io::{self, IsTerminal},
     path::{Path, PathBuf},
 };
 use timeflippers::{
-    timeflip::{Entry, Event, TimeFlip},
+    export::{self, InfluxDestination},
+    store::EntryStore,
+    timeflip::{Connection, Entry, Event, TimeFlip},
     view, BluetoothSession, Config,
 };
 use tokio::{fs, select, signal};
@@ -17,6 +19,90 @@ async fn read_config(path: impl AsRef<Path>) -> anyhow::Result<Config> {
     Ok(config)
 }
 
+/// Read new entries from the TimeFlip2, merging them with any cached in `update_file`.
+async fn load_entries(
+    timeflip: &TimeFlip,
+    update_file: &Option<PathBuf>,
+    start_with: u32,
+) -> anyhow::Result<Vec<Entry>> {
+    let (start_with, mut entries) = if let Some(file) = update_file {
+        match fs::read_to_string(file).await {
+            Ok(s) => {
+                let mut entries: Vec<Entry> = serde_json::from_str(&s)?;
+                entries.sort_by(|a, b| a.id.cmp(&b.id));
+                (entries.last().map(|e| e.id).unwrap_or(0), entries)
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => (0, vec![]),
+            Err(e) => return Err(e.into()),
+        }
+    } else {
+        (start_with, vec![])
+    };
+
+    let mut update = timeflip.read_history_since(start_with).await?;
+
+    let new_ids = update.iter().map(|e| e.id).collect::<Vec<_>>();
+    entries.retain(|entry| !new_ids.contains(&entry.id));
+    entries.append(&mut update);
+
+    if let Some(file) = update_file {
+        match serde_json::to_vec(&entries) {
+            Ok(json) => {
+                if let Err(e) = fs::write(file, json).await {
+                    eprintln!("cannot update entries file {}: {e}", file.display());
+                }
+            }
+            Err(e) => eprintln!("cannot update entries file {}: {e}", file.display()),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Backfill the [EntryStore] with any entries logged since its last persisted id, logging each
+/// one resolved to its facet's configured name/task.
+async fn backfill(
+    timeflip: &TimeFlip,
+    store: &mut EntryStore,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let entries = timeflip.read_history_since(store.highest_id()).await?;
+    for entry in &entries {
+        let side = &config.sides[entry.facet.index_zero()];
+        let name = side
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Side {}", entry.facet.index_zero()));
+        log::info!(
+            "{name} ({}): {} seconds",
+            side.task,
+            entry.duration.as_secs()
+        );
+    }
+    store.append(&entries).await?;
+    Ok(())
+}
+
+/// Narrow a [view::History] down to entries matching `range` (see [view::range]), or after
+/// `since`, or all of them. `range` and `since` are mutually exclusive (enforced by clap).
+fn filter_history<'a>(
+    history: &'a view::History,
+    since: Option<NaiveDate>,
+    range: Option<&str>,
+) -> anyhow::Result<view::HistoryFiltered<'a>> {
+    if let Some(range) = range {
+        Ok(history.range(range)?)
+    } else if let Some(since) = since {
+        let date = DateTime::<Local>::from_local(
+            since.and_hms_opt(0, 0, 0).expect("is a valid time"),
+            *Local::now().offset(),
+        );
+        Ok(history.since(date.into()))
+    } else {
+        Ok(history.all())
+    }
+}
+
 /// Communicate with a TimeFlip2 cube.
 ///
 /// Note: Use `bluetoothctl` to pair (and potentially connect) the TimeFlip2.
@@ -33,6 +119,25 @@ enum HistoryStyle {
     Lines,
     Tabular,
     Summarized,
+    Csv,
+    Json,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
+enum HistoryGranularity {
+    Day,
+    Week,
+    Month,
+}
+
+impl From<HistoryGranularity> for view::Granularity {
+    fn from(granularity: HistoryGranularity) -> Self {
+        match granularity {
+            HistoryGranularity::Day => view::Granularity::Day,
+            HistoryGranularity::Week => view::Granularity::Week,
+            HistoryGranularity::Month => view::Granularity::Month,
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -51,10 +156,57 @@ enum Command {
             default_value = "0"
         )]
         start_with: u32,
-        #[arg(long, help = "start displaying with entries after DATE (YYYY-MM-DD)")]
+        #[arg(
+            long,
+            help = "start displaying with entries after DATE (YYYY-MM-DD)",
+            conflicts_with = "range"
+        )]
         since: Option<NaiveDate>,
+        #[arg(
+            long,
+            help = "display entries in a relative range, e.g. \"today\"/\"3 days ago\""
+        )]
+        range: Option<String>,
         #[arg(long, help = "choose output style", default_value = "tabular")]
         style: HistoryStyle,
+        #[arg(long, help = "round durations to the nearest whole minute")]
+        rounded: bool,
+        #[arg(
+            long,
+            help = "bucket tabular/summarized output by day, week or month",
+            default_value = "day"
+        )]
+        granularity: HistoryGranularity,
+    },
+    /// Export logged TimeFlip events as InfluxDB line protocol points.
+    Export {
+        #[arg(help = "path to the timeflip.toml file")]
+        config: PathBuf,
+        #[arg(long, help = "read events from and write new events to file")]
+        update: Option<PathBuf>,
+        #[arg(
+            long,
+            help = "start reading with entry ID, latest event in `--update` takes precedence",
+            default_value = "0"
+        )]
+        start_with: u32,
+        #[arg(
+            long,
+            help = "only export entries after DATE (YYYY-MM-DD)",
+            conflicts_with = "range"
+        )]
+        since: Option<NaiveDate>,
+        #[arg(
+            long,
+            help = "only export entries in a relative range, e.g. \"today\"/\"3 days ago\""
+        )]
+        range: Option<String>,
+        #[arg(long, help = "base URL of the InfluxDB instance")]
+        influx_url: String,
+        #[arg(long, help = "InfluxDB bucket to write points to")]
+        bucket: String,
+        #[arg(long, help = "InfluxDB authentication token")]
+        token: String,
     },
     /// Print the facet currently facing up.
     Facet,
@@ -88,11 +240,23 @@ enum Command {
     },
     /// Get the TimeFlip2's current time.
     Time,
+    /// Continuously persist logged entries to a crash-safe on-disk store until interrupted.
+    Track {
+        #[arg(help = "path to the timeflip.toml file")]
+        config: PathBuf,
+        #[arg(help = "path to the newline-delimited JSON entry store")]
+        store: PathBuf,
+    },
     /// Write config from the toml file to the TimeFlip2's memory.
     WriteConfig {
         #[arg(help = "path to the timeflip.toml file")]
         config: PathBuf,
     },
+    /// Read the TimeFlip2's current configuration and write it out as a toml file.
+    DumpConfig {
+        #[arg(help = "path to write the timeflip.toml file to")]
+        out: PathBuf,
+    },
 }
 
 impl Command {
@@ -108,57 +272,68 @@ impl Command {
                 start_with,
                 style,
                 since,
+                range,
+                rounded,
+                granularity,
             } => {
                 let config = read_config(config).await?;
+                let entries = load_entries(timeflip, update_file, *start_with).await?;
 
-                let (start_with, mut entries) = if let Some(file) = update_file {
-                    match fs::read_to_string(file).await {
-                        Ok(s) => {
-                            let mut entries: Vec<Entry> = serde_json::from_str(&s)?;
-                            entries.sort_by(|a, b| a.id.cmp(&b.id));
-                            (entries.last().map(|e| e.id).unwrap_or(0), entries)
+                let history = view::History::new(entries, config);
+                let filtered = filter_history(&history, *since, range.as_deref())?;
+                let colorize = io::stdout().is_terminal();
+                let granularity = view::Granularity::from(*granularity);
+                use HistoryStyle::*;
+                match style {
+                    Lines => println!("{}", filtered),
+                    Tabular => {
+                        let table = filtered.table_by(granularity);
+                        let table = if *rounded { table.rounded() } else { table };
+                        if colorize {
+                            println!("{}", table.colored());
+                        } else {
+                            println!("{}", table);
                         }
-                        Err(e) if e.kind() == io::ErrorKind::NotFound => (0, vec![]),
-                        Err(e) => return Err(e.into()),
                     }
-                } else {
-                    (*start_with, vec![])
-                };
-
-                let mut update = timeflip.read_history_since(start_with).await?;
-
-                let new_ids = update.iter().map(|e| e.id).collect::<Vec<_>>();
-                entries.retain(|entry| !new_ids.contains(&entry.id));
-                entries.append(&mut update);
-
-                if let Some(file) = update_file {
-                    match serde_json::to_vec(&entries) {
-                        Ok(json) => {
-                            if let Err(e) = fs::write(file, json).await {
-                                eprintln!("cannot update entries file {}: {e}", file.display());
-                            }
+                    Summarized => {
+                        let summarized = filtered.summarized_by(granularity);
+                        let summarized = if *rounded {
+                            summarized.rounded()
+                        } else {
+                            summarized
+                        };
+                        if colorize {
+                            println!("{}", summarized.colored());
+                        } else {
+                            println!("{}", summarized);
                         }
-                        Err(e) => eprintln!("cannot update entries file {}: {e}", file.display()),
                     }
+                    Csv => print!("{}", filtered.report().to_csv()),
+                    Json => println!("{}", serde_json::to_string_pretty(&filtered.report())?),
                 }
+            }
+            Export {
+                config,
+                update: update_file,
+                start_with,
+                since,
+                range,
+                influx_url,
+                bucket,
+                token,
+            } => {
+                let config = read_config(config).await?;
+                let entries = load_entries(timeflip, update_file, *start_with).await?;
 
                 let history = view::History::new(entries, config);
-                let filtered = if let Some(since) = since {
-                    let date = DateTime::<Local>::from_local(
-                        since.and_hms_opt(0, 0, 0).expect("is a valid time"),
-                        *Local::now().offset(),
-                    );
+                let filtered = filter_history(&history, *since, range.as_deref())?;
 
-                    history.since(date.into())
-                } else {
-                    history.all()
+                let destination = InfluxDestination {
+                    url: influx_url.clone(),
+                    bucket: bucket.clone(),
+                    token: token.clone(),
                 };
-                use HistoryStyle::*;
-                match style {
-                    Lines => println!("{}", filtered),
-                    Tabular => println!("{}", filtered.table_by_day()),
-                    Summarized => println!("{}", filtered.summarized()),
-                }
+                export::export(filtered.resolved(), &destination).await?;
             }
             Facet => {
                 println!("Currently up: {:?}", timeflip.facet().await?);
@@ -194,6 +369,8 @@ impl Command {
                             "Facet {facet} has {}",
                             if pause { "paused" } else { "started" }
                         ),
+                        Some(Event::Connected) => println!("TimeFlip has connected"),
+                        Some(Event::ServicesResolved) => println!("TimeFlip services resolved"),
                         Some(Event::Disconnected) => {
                             println!("TimeFlip has disconnected");
                             break;
@@ -219,10 +396,42 @@ impl Command {
                 let time = timeflip.time().await?;
                 println!("Time set on TimeFlip: {}", time.with_timezone(&tz));
             }
+            Track { config, store } => {
+                let config = read_config(config).await?;
+                let mut store = EntryStore::open(store).await?;
+
+                timeflip.subscribe_facet().await?;
+                timeflip.subscribe_double_tap().await?;
+                timeflip.subscribe_events().await?;
+                backfill(timeflip, &mut store, &config).await?;
+
+                // `conn` reconnects its own TimeFlip in place, so every `backfill` below reads
+                // from `conn.timeflip()` instead of the original `timeflip`, which would be left
+                // with stale characteristics after the first reconnect.
+                let mut conn = Connection::new(timeflip);
+                loop {
+                    match conn.next_event().await {
+                        Event::Disconnected => {
+                            log::warn!("TimeFlip has disconnected, will backfill on reconnect");
+                        }
+                        Event::Facet(_) | Event::DoubleTap { .. } => {
+                            if let Some(timeflip) = conn.timeflip() {
+                                backfill(timeflip, &mut store, &config).await?;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
             WriteConfig { config } => {
                 let config = read_config(config).await?;
                 timeflip.write_config(config).await?;
             }
+            DumpConfig { out } => {
+                let config = timeflip.read_config().await?;
+                let toml = toml::to_string(&config)?;
+                fs::write(out, toml).await?;
+            }
         }
         Ok(())
     }
@@ -1,13 +1,13 @@
 use crate::types::{BlinkInterval, Color, Facet, FacetError, FacetTask, Minutes, Percent};
 use serde::{
     de::{self, Error},
-    Deserialize,
+    ser, Deserialize, Serialize,
 };
 use std::default::Default;
 use thiserror::Error as ThisError;
 
 /// Configuration of a TimeFlip2 facet.
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Side {
     /// The name of the facet.
     pub facet: Facet,
@@ -32,7 +32,7 @@ impl Side {
 }
 
 /// Configuration of a TimeFlip2.
-#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename = "Timeflip")]
 pub struct Config {
     /// The password to access the TimeFlip2.
@@ -44,7 +44,10 @@ pub struct Config {
     /// Time after which activity is automatically paused.
     pub auto_pause: Minutes,
     /// Configuration for each facet/side.
-    #[serde(deserialize_with = "deserialize_sides")]
+    #[serde(
+        deserialize_with = "deserialize_sides",
+        serialize_with = "serialize_sides"
+    )]
     pub sides: [Side; 12],
 }
 
@@ -95,3 +98,10 @@ where
     let sides = Vec::<Side>::deserialize(deserializer)?;
     sides_from_vec(sides).map_err(Error::custom)
 }
+
+fn serialize_sides<S>(sides: &[Side; 12], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+{
+    sides.as_slice().serialize(serializer)
+}
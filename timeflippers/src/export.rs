@@ -0,0 +1,115 @@
+//! Export of TimeFlip2 history as InfluxDB line protocol, shipped to a time-series database.
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::view::ResolvedEntry;
+
+/// Number of points bundled into a single HTTP write request.
+const BATCH_SIZE: usize = 500;
+/// Initial delay before retrying a failed write.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound on the backoff delay between write retries.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Number of attempts a batch write is given before giving up.
+const MAX_ATTEMPTS: usize = 5;
+
+/// Error while exporting history to InfluxDB.
+#[derive(Debug, Error)]
+pub enum ExportError {
+    /// A batch could not be written after [MAX_ATTEMPTS] attempts.
+    #[error("giving up writing a batch to InfluxDB after {0} attempts: {1}")]
+    GivingUp(usize, String),
+}
+
+/// Connection details for an InfluxDB write endpoint.
+pub struct InfluxDestination {
+    /// Base URL of the InfluxDB instance, e.g. `http://localhost:8086`.
+    pub url: String,
+    /// Bucket to write points to.
+    pub bucket: String,
+    /// Authentication token.
+    pub token: String,
+}
+
+/// Escape a tag key or value per the line protocol (commas, spaces and equals signs).
+fn escape_tag(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(' ', "\\ ")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+}
+
+/// Render a single [ResolvedEntry] as one InfluxDB line protocol point.
+///
+/// Paused entries mark a gap rather than logged activity, so they are skipped.
+fn to_line(resolved: &ResolvedEntry) -> Option<String> {
+    if resolved.entry.pause {
+        return None;
+    }
+
+    let nanos = resolved.entry.time.and_utc().timestamp_nanos_opt()?;
+    Some(format!(
+        "timeflip,facet={},task={} duration={}i {}",
+        resolved.entry.facet.index(),
+        escape_tag(&resolved.task.to_string()),
+        resolved.entry.duration.as_secs(),
+        nanos,
+    ))
+}
+
+/// Convert `entries` into line protocol points and write them to `destination` in batches of
+/// [BATCH_SIZE], retrying each batch with exponential backoff on failure.
+pub async fn export<'a>(
+    entries: impl Iterator<Item = ResolvedEntry<'a>>,
+    destination: &InfluxDestination,
+) -> Result<(), ExportError> {
+    let points: Vec<String> = entries.filter_map(|resolved| to_line(&resolved)).collect();
+
+    let client = reqwest::Client::new();
+    let url = format!(
+        "{}/api/v2/write?bucket={}&precision=ns",
+        destination.url, destination.bucket
+    );
+
+    for batch in points.chunks(BATCH_SIZE) {
+        write_batch(&client, &url, &destination.token, &batch.join("\n")).await?;
+    }
+
+    Ok(())
+}
+
+/// Write a single batch of already-rendered line protocol points, retrying with backoff.
+async fn write_batch(
+    client: &reqwest::Client,
+    url: &str,
+    token: &str,
+    body: &str,
+) -> Result<(), ExportError> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut last_error = String::new();
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let result = client
+            .post(url)
+            .header("Authorization", format!("Token {token}"))
+            .body(body.to_owned())
+            .send()
+            .await;
+
+        match result {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => last_error = format!("InfluxDB rejected the write: {}", response.status()),
+            Err(e) => last_error = e.to_string(),
+        }
+
+        if attempt < MAX_ATTEMPTS {
+            log::warn!("influx write failed: {last_error}, retrying in {backoff:?}");
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    Err(ExportError::GivingUp(MAX_ATTEMPTS, last_error))
+}
@@ -5,11 +5,15 @@ pub use timeflip::TimeFlip;
 
 pub mod view;
 
+pub mod export;
+
+pub mod store;
+
 mod config;
 pub use config::Config;
 
 mod types;
 pub use types::{
-    BlinkInterval, BlinkIntervalError, Color, Facet, FacetError, FacetTask, Minutes, Percent,
-    PercentError,
+    BlinkInterval, BlinkIntervalError, Color, ColorError, Facet, FacetError, FacetTask, Minutes,
+    Percent, PercentError,
 };
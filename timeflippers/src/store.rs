@@ -0,0 +1,116 @@
+//! Crash-safe, append-only on-disk store for TimeFlip2 history entries.
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+};
+
+use crate::timeflip::Entry;
+
+/// Error reading or writing an [EntryStore].
+#[derive(Debug, Error)]
+pub enum StoreError {
+    /// I/O error reading, writing or renaming the store file.
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    /// A stored record or an entry to be appended could not be (de-)serialized.
+    #[error("{0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Append-only, newline-delimited JSON store of [Entry] records.
+///
+/// Each line is a single JSON-encoded [Entry], written and flushed individually so a crash
+/// mid-write leaves every prior line intact; at worst the final line is truncated, which
+/// [EntryStore::open] tolerates by skipping it. Records are deduplicated by id on open, keeping
+/// the last occurrence of a given id; if that uncovers any duplicates or any record was skipped
+/// as unparsable, the store is rewritten through a temp file and renamed into place before
+/// appending resumes.
+pub struct EntryStore {
+    path: PathBuf,
+    file: File,
+    highest_id: u32,
+}
+
+impl EntryStore {
+    /// Open (creating if necessary) the store at `path`, deduplicating existing records.
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, StoreError> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut by_id = HashMap::<u32, Entry>::new();
+        let mut total_lines = 0;
+        if let Ok(file) = File::open(&path).await {
+            let mut lines = BufReader::new(file).lines();
+            while let Some(line) = lines.next_line().await? {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                total_lines += 1;
+                match serde_json::from_str::<Entry>(&line) {
+                    Ok(entry) => {
+                        by_id.insert(entry.id, entry);
+                    }
+                    Err(e) => log::warn!("skipping unparsable record in {}: {e}", path.display()),
+                }
+            }
+        }
+
+        let highest_id = by_id.keys().copied().max().unwrap_or(0);
+
+        if by_id.len() != total_lines {
+            let mut entries: Vec<&Entry> = by_id.values().collect();
+            entries.sort_by_key(|entry| entry.id);
+            Self::rewrite(&path, &entries).await?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+
+        Ok(EntryStore {
+            path,
+            file,
+            highest_id,
+        })
+    }
+
+    /// The highest entry id seen so far, usable as the starting point for backfilling.
+    pub fn highest_id(&self) -> u32 {
+        self.highest_id
+    }
+
+    /// Append `entries` to the store, one JSON object per line, flushing after each write.
+    pub async fn append(&mut self, entries: &[Entry]) -> Result<(), StoreError> {
+        for entry in entries {
+            let mut line = serde_json::to_string(entry)?;
+            line.push('\n');
+            self.file.write_all(line.as_bytes()).await?;
+            self.file.flush().await?;
+            self.highest_id = self.highest_id.max(entry.id);
+        }
+        Ok(())
+    }
+
+    /// Atomically replace the store's content with `entries`, via a temp file and rename.
+    async fn rewrite(path: &Path, entries: &[&Entry]) -> Result<(), StoreError> {
+        let tmp_path = path.with_extension("tmp");
+
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+
+        fs::write(&tmp_path, content).await?;
+        fs::rename(&tmp_path, path).await?;
+        Ok(())
+    }
+}
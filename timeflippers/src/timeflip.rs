@@ -1,15 +1,15 @@
 //! Communicating with TimeFlip2
 #![deny(missing_docs)]
 
-use bluez_async::{
-    BluetoothError, BluetoothEvent, BluetoothSession, CharacteristicEvent, CharacteristicInfo,
-    DeviceInfo,
-};
+use bluez_async::{BluetoothError, BluetoothSession, DeviceInfo, MacAddress};
 use bytes::BufMut;
 use chrono::{DateTime, Utc};
 use futures::stream::{BoxStream, StreamExt};
-use std::{convert::Infallible, string::FromUtf8Error};
+use std::{
+    collections::HashSet, convert::Infallible, string::FromUtf8Error, sync::Mutex, time::Duration,
+};
 use thiserror::Error;
+use tokio::time::timeout;
 
 use crate::{
     config::Config,
@@ -17,7 +17,38 @@ use crate::{
 };
 
 mod gatt;
-pub use gatt::{Entry, Event, FacetSettings, SyncState, SyncType, SystemStatus};
+pub use gatt::{
+    DeviceInfo as TimeFlipDeviceInfo, Entry, Event, FacetSettings, SyncState, SyncType,
+    SystemStatus,
+};
+
+mod transport;
+pub use transport::{BlueZTransport, Transport, TransportEvent};
+#[cfg(feature = "btleplug")]
+pub use transport::BtleplugTransport;
+#[cfg(feature = "btleplug")]
+use transport::BtleplugError;
+
+mod synchronizer;
+pub use synchronizer::{SyncWarnings, Synchronizer};
+
+mod history;
+pub use history::HistoryReader;
+
+mod connection;
+pub use connection::{Connection, ConnectionState};
+
+mod actor;
+pub use actor::TimeFlipHandle;
+
+mod registry;
+pub use registry::{TaggedEvent, TimeFlipRegistry};
+
+mod pairing;
+pub use pairing::PairingError;
+
+mod battery;
+pub use battery::{BatteryAlert, Direction, Threshold};
 
 /// Error for communication with TimeFlip2.
 #[allow(missing_docs)]
@@ -29,6 +60,10 @@ pub enum Error {
     InvalidCommand(u8),
     #[error("command execution failed")]
     CommandExecutionFailed,
+    #[error("command timed out waiting for TimeFlip2 to respond")]
+    CommandTimeout,
+    #[error("TimeFlip2 requires the password to be (re-)written")]
+    PasswordRequired,
     #[error("{0}")]
     GetTime(#[from] gatt::GetTimeError),
     #[error("{0}")]
@@ -39,6 +74,8 @@ pub enum Error {
     InvalidFacet(#[from] FacetError),
     #[error("invalid facet settings: {0}")]
     InvalidFacetSettings(#[from] gatt::FacetSettingsError),
+    #[error("invalid double-tap setting: {0}")]
+    InvalidDoubleTap(#[from] gatt::DoubleTapError),
     #[error("characteristic read returned invalid data: {0}")]
     InvalidCharacteristicData(String),
     #[error("invalid history entry: {0}")]
@@ -49,14 +86,17 @@ pub enum Error {
     InvalidSystemStatus(#[from] gatt::SystemStatusError),
     #[error("{0}")]
     Bluetooth(#[from] BluetoothError),
+    #[cfg(feature = "btleplug")]
+    #[error("{0}")]
+    Btleplug(#[from] BtleplugError),
     #[error("no TimeFlip2 bluetooth device found")]
     NoDevice,
-    #[error("TimeFlip2 reports Accelerometer error")]
-    AccelerometerError,
-    #[error("TimeFlip2 reports Flash error")]
-    FlashError,
     #[error("Could not synchronize {0:?}")]
     SyncError(SyncType),
+    #[error("the TimeFlipHandle's background task is no longer running")]
+    ActorStopped,
+    #[error("{0}")]
+    Pairing(#[from] PairingError),
 }
 
 impl From<Infallible> for Error {
@@ -65,40 +105,110 @@ impl From<Infallible> for Error {
     }
 }
 
+/// A notification subscription that [TimeFlip::reconnect] must re-issue after a reconnect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Subscription {
+    /// [TimeFlip::subscribe_battery_level]
+    BatteryLevel,
+    /// [TimeFlip::subscribe_events]
+    Event,
+    /// [TimeFlip::subscribe_facet]
+    Facet,
+    /// [TimeFlip::subscribe_double_tap]
+    DoubleTap,
+}
+
 /// Handles to TimeFlip2's characteristics.
 ///
-/// We need the CharacteristicInfo, which is bound to the bluez device, for accessing the dice's
-/// attributes, hence we have to query it once during initialization.
+/// We need the characteristic handle resolved through the backend [Transport] for accessing the
+/// dice's attributes, hence we have to query it once during initialization.
 #[derive(Debug, Clone)]
-struct CharacteristicHandles {
-    battery_level: CharacteristicInfo,
-    event: CharacteristicInfo,
-    facet: CharacteristicInfo,
-    command_result: CharacteristicInfo,
-    command: CharacteristicInfo,
-    double_tap: CharacteristicInfo,
-    system_state: CharacteristicInfo,
-    password: CharacteristicInfo,
-    history: CharacteristicInfo,
+struct CharacteristicHandles<T: Transport> {
+    battery_level: T::CharacteristicHandle,
+    event: T::CharacteristicHandle,
+    facet: T::CharacteristicHandle,
+    command_result: T::CharacteristicHandle,
+    command: T::CharacteristicHandle,
+    double_tap: T::CharacteristicHandle,
+    system_state: T::CharacteristicHandle,
+    password: T::CharacteristicHandle,
+    history: T::CharacteristicHandle,
 }
 
 /// Representation of a TimeFlip2 dice connected via Bluetooth.
+///
+/// Generic over the [Transport] used to talk to the device, defaulting to [BlueZTransport] (BlueZ
+/// on Linux). A `btleplug`-based [BtleplugTransport] is available behind the `btleplug` feature
+/// for macOS/Windows.
 #[derive(Debug)]
-pub struct TimeFlip {
-    /// Handle to the dbus session communicating with bluez.
-    session: BluetoothSession,
-    /// Handle for the TimeFlip2 Bluetooth device
-    device: DeviceInfo,
+pub struct TimeFlip<T: Transport = BlueZTransport> {
+    /// Handle to the backend transport used to communicate with TimeFlip2.
+    transport: T,
+    /// Handle for the TimeFlip2 Bluetooth device.
+    device: T::DeviceHandle,
     /// Handle to each of the device's characteristics.
-    characteristics: CharacteristicHandles,
+    characteristics: CharacteristicHandles<T>,
     /// Password to write to the TimeFlip2's password characteristic when connecting.
     password: [u8; 6],
+    /// Notifications the caller has enabled so far, re-issued by [TimeFlip::reconnect].
+    subscriptions: Mutex<HashSet<Subscription>>,
 }
 
-impl TimeFlip {
+/// A TimeFlip2 device found via [TimeFlip::discover], not yet connected to.
+#[derive(Debug, Clone)]
+pub struct TimeFlipCandidate {
+    /// The device's advertised name, if any.
+    pub name: Option<String>,
+    /// The device's Bluetooth MAC address, to be passed to [TimeFlip::connect_to].
+    pub mac_address: MacAddress,
+    /// Whether the device is currently paired with the adapter.
+    pub paired: bool,
+    /// Whether the device is currently connected.
+    pub connected: bool,
+}
+
+impl TimeFlip<BlueZTransport> {
+    /// List every device the adapter knows about that announces the TimeFlip service.
+    ///
+    /// Like [TimeFlip::connect], this relies on the device already being known to the adapter, so
+    /// pairing should be done with `bluetoothctl` first.
+    pub async fn discover(session: &BluetoothSession) -> Result<Vec<TimeFlipCandidate>, Error> {
+        Ok(Self::find_devices(session)
+            .await?
+            .into_iter()
+            .map(|device| TimeFlipCandidate {
+                name: device.name,
+                mac_address: device.mac_address,
+                paired: device.paired,
+                connected: device.connected,
+            })
+            .collect())
+    }
+
+    /// List every known device announcing the TimeFlip service, logging each one found.
+    async fn find_devices(session: &BluetoothSession) -> Result<Vec<DeviceInfo>, Error> {
+        let time_flip_service_id = gatt::Service::TimeFlip.uuid();
+        Ok(session
+            .get_devices()
+            .await?
+            .into_iter()
+            .filter(|dev| {
+                log::debug!(
+                    "found device {} ({})",
+                    dev.name.as_deref().unwrap_or("<unknown>"),
+                    dev.mac_address
+                );
+                dev.services
+                    .iter()
+                    .any(|service| *service == time_flip_service_id)
+            })
+            .collect())
+    }
+
     /// Discover devices announcing the TimeFlip service and connect to it.
     ///
-    /// Currently, the first TimeFlip2 encountered is selected.
+    /// Currently, the first TimeFlip2 encountered is selected; use [TimeFlip::connect_to] to
+    /// target a specific dice when several are paired.
     ///
     /// Pairing should be done with `bluetoothctl` first.
     ///
@@ -108,32 +218,57 @@ impl TimeFlip {
         session: &BluetoothSession,
         password: Option<[u8; 6]>,
     ) -> Result<Self, Error> {
-        let time_flip_service_id = gatt::Service::TimeFlip.uuid();
-
-        let device = if let Some(device) = session.get_devices().await?.into_iter().find(|dev| {
-            log::debug!(
-                "found device {} ({})",
-                dev.name.as_deref().unwrap_or("<unknown>"),
-                dev.mac_address
-            );
-            dev.services
-                .iter()
-                .any(|service| *service == time_flip_service_id)
-        }) {
-            device
-        } else {
-            // If the TimeFlip2 is paired, it should be present in the adapter's device list
-            // regardless of whether or not it is in range.
-            //
-            // It seems that bluez_async does not support pairing at the moment, hence we rely
-            // on bluetoothctl for that.
-            log::warn!(
-                "no devices are found, this probably means the TimeFlip2 is not paired,
+        // If the TimeFlip2 is paired, it should be present in the adapter's device list
+        // regardless of whether or not it is in range.
+        //
+        // It seems that bluez_async does not support pairing at the moment, hence we rely
+        // on bluetoothctl for that.
+        let device = Self::find_devices(session)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| {
+                log::warn!(
+                    "no devices are found, this probably means the TimeFlip2 is not paired,
                  please pair via `bluetoothctl`"
-            );
-            return Err(Error::NoDevice);
-        };
+                );
+                Error::NoDevice
+            })?;
+
+        Self::connect_device(session, device, password).await
+    }
+
+    /// Pair with, trust and bond the TimeFlip2 at `mac_address`, without requiring the user to
+    /// run `bluetoothctl` first.
+    ///
+    /// See [TimeFlip::discover] for a way to enumerate candidate MAC addresses.
+    pub async fn pair(session: &BluetoothSession, mac_address: MacAddress) -> Result<(), Error> {
+        Ok(pairing::pair(session, mac_address).await?)
+    }
+
+    /// Connect to the TimeFlip2 with the given MAC address.
+    ///
+    /// See [TimeFlip::discover] for a way to enumerate candidate MAC addresses.
+    pub async fn connect_to(
+        session: &BluetoothSession,
+        mac_address: MacAddress,
+        password: Option<[u8; 6]>,
+    ) -> Result<Self, Error> {
+        let device = Self::find_devices(session)
+            .await?
+            .into_iter()
+            .find(|dev| dev.mac_address == mac_address)
+            .ok_or(Error::NoDevice)?;
 
+        Self::connect_device(session, device, password).await
+    }
+
+    /// Ensure `device` is connected and resolve a [TimeFlip] from it.
+    async fn connect_device(
+        session: &BluetoothSession,
+        device: DeviceInfo,
+        password: Option<[u8; 6]>,
+    ) -> Result<Self, Error> {
         if !device.paired {
             log::warn!("device is not paired");
         }
@@ -151,23 +286,44 @@ impl TimeFlip {
             session.connect(&device.id).await?;
         }
 
-        use gatt::Characteristic::*;
-        let id = device.id.clone();
+        let transport = BlueZTransport::new(session.clone());
+        let device_id = device.id.clone();
+        Self::from_transport(transport, device_id, password).await
+    }
+}
+
+/// Timeout for a single round-trip while waiting for TimeFlip2 to acknowledge or answer a
+/// command.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+/// Delay between polls of the CommandResult characteristic while discarding a stale result.
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Status byte reported on the Command characteristic once TimeFlip2 has executed a command.
+mod command_status {
+    /// The command was executed successfully.
+    pub const SUCCESS: u8 = 2;
+    /// The command was rejected because the password has not been (re-)written.
+    pub const PASSWORD_ERROR: u8 = 1;
+}
+
+impl<T: Transport> TimeFlip<T>
+where
+    Error: From<T::Error>,
+{
+    /// Resolve all of TimeFlip2's characteristics on an already-connected device and write the
+    /// password.
+    pub async fn from_transport(
+        transport: T,
+        device: T::DeviceHandle,
+        password: Option<[u8; 6]>,
+    ) -> Result<Self, Error> {
+        let characteristics = Self::resolve_characteristics(&transport, &device).await?;
         let timeflip = TimeFlip {
-            session: session.clone(),
+            characteristics,
             device,
-            characteristics: CharacteristicHandles {
-                battery_level: BatteryLevel.get_info(session, &id).await?,
-                event: Event.get_info(session, &id).await?,
-                facet: Facet.get_info(session, &id).await?,
-                command_result: CommandResult.get_info(session, &id).await?,
-                command: Command.get_info(session, &id).await?,
-                double_tap: DoubleTap.get_info(session, &id).await?,
-                system_state: SystemState.get_info(session, &id).await?,
-                password: Password.get_info(session, &id).await?,
-                history: History.get_info(session, &id).await?,
-            },
+            transport,
             password: password.unwrap_or([0x30; 6]),
+            subscriptions: Mutex::new(HashSet::new()),
         };
 
         timeflip.write_password().await?;
@@ -175,25 +331,99 @@ impl TimeFlip {
         Ok(timeflip)
     }
 
+    /// Resolve handles to all of TimeFlip2's characteristics, as needed once at connect time and
+    /// again on every [TimeFlip::reconnect] since a reconnect may invalidate them.
+    async fn resolve_characteristics(
+        transport: &T,
+        device: &T::DeviceHandle,
+    ) -> Result<CharacteristicHandles<T>, Error> {
+        use gatt::Characteristic::*;
+        Ok(CharacteristicHandles {
+            battery_level: BatteryLevel.get_info(transport, device).await?,
+            event: Event.get_info(transport, device).await?,
+            facet: Facet.get_info(transport, device).await?,
+            command_result: CommandResult.get_info(transport, device).await?,
+            command: Command.get_info(transport, device).await?,
+            double_tap: DoubleTap.get_info(transport, device).await?,
+            system_state: SystemState.get_info(transport, device).await?,
+            password: Password.get_info(transport, device).await?,
+            history: History.get_info(transport, device).await?,
+        })
+    }
+
+    /// Reconnect to a TimeFlip2 that has dropped out of range.
+    ///
+    /// Re-connects the underlying transport, re-writes the password, re-resolves every
+    /// characteristic handle (they may be stale after a reconnect) and re-issues every
+    /// notification subscription the caller had previously enabled via one of the
+    /// `subscribe_*` methods, so that a stream obtained from [TimeFlip::event_stream] keeps
+    /// yielding events once the device is back in range.
+    pub async fn reconnect(&mut self) -> Result<(), Error> {
+        self.transport.connect(&self.device).await?;
+
+        self.characteristics = Self::resolve_characteristics(&self.transport, &self.device).await?;
+        self.write_password().await?;
+
+        let subscriptions = self.subscriptions.lock().unwrap().clone();
+        for subscription in subscriptions {
+            let handle = match subscription {
+                Subscription::BatteryLevel => &self.characteristics.battery_level,
+                Subscription::Event => &self.characteristics.event,
+                Subscription::Facet => &self.characteristics.facet,
+                Subscription::DoubleTap => &self.characteristics.double_tap,
+            };
+            self.transport.subscribe(handle).await?;
+        }
+
+        Ok(())
+    }
+
     /// Disconnect the bluetooth device.
-    pub async fn disconnect(&self) -> Result<(), Error> {
-        Ok(self.session.disconnect(&self.device.id).await?)
+    pub async fn disconnect(&self) -> Result<(), Error>
+    where
+        T: DisconnectableTransport,
+    {
+        self.transport.disconnect(&self.device).await?;
+        Ok(())
     }
 
     /// Write the password to access TimeFlip2's properties properly.
     async fn write_password(&self) -> Result<(), Error> {
         log::debug!("writing password");
-        self.session
-            .write_characteristic_value(&self.characteristics.password.id, self.password)
+        self.transport
+            .write(&self.characteristics.password, self.password.to_vec())
             .await?;
         Ok(())
     }
 
+    /// Read TimeFlip2's firmware/hardware identity from the standard Device Information service.
+    pub async fn device_info(&self) -> Result<gatt::DeviceInfo, Error> {
+        use gatt::Characteristic::*;
+        Ok(gatt::DeviceInfo {
+            manufacturer_name: self.read_string_characteristic(ManufacturerName).await?,
+            firmware_revision: self.read_string_characteristic(FirmwareRevision).await?,
+            hardware_revision: self.read_string_characteristic(HardwareRevision).await?,
+            serial_number: self.read_string_characteristic(SerialNumber).await?,
+        })
+    }
+
+    /// Read a standard UTF-8 string characteristic not resolved at connect time.
+    async fn read_string_characteristic(
+        &self,
+        characteristic: gatt::Characteristic,
+    ) -> Result<String, Error> {
+        let handle = characteristic
+            .get_info(&self.transport, &self.device)
+            .await?;
+        let data = self.transport.read(&handle).await?;
+        Ok(String::from_utf8(data)?)
+    }
+
     /// Get the TimeFlip2's battery level in percent.
     pub async fn battery_level(&self) -> Result<Percent, Error> {
         let data = self
-            .session
-            .read_characteristic_value(&self.characteristics.battery_level.id)
+            .transport
+            .read(&self.characteristics.battery_level)
             .await?;
 
         match data.first() {
@@ -204,36 +434,51 @@ impl TimeFlip {
 
     /// Subscribe for [Event::BatteryLevel] events.
     pub async fn subscribe_battery_level(&self) -> Result<(), Error> {
-        self.session
-            .start_notify(&self.characteristics.battery_level.id)
-            .await
-            .map_err(Into::into)
+        self.transport
+            .subscribe(&self.characteristics.battery_level)
+            .await?;
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .insert(Subscription::BatteryLevel);
+        Ok(())
+    }
+
+    /// Watch the battery level and emit a [BatteryAlert] whenever it crosses one of
+    /// `thresholds`, rather than on every notification.
+    ///
+    /// Subscribes for [Event::BatteryLevel] events if not already subscribed.
+    pub async fn battery_watcher(
+        &self,
+        thresholds: Vec<Threshold>,
+    ) -> Result<BoxStream<'static, BatteryAlert>, Error> {
+        self.subscribe_battery_level().await?;
+        let events = self.event_stream().await?;
+        Ok(battery::watch(events, thresholds))
     }
 
     /// Read the (informational) last event of the TimeFlip2.
     pub async fn last_event(&self) -> Result<String, Error> {
-        let data = self
-            .session
-            .read_characteristic_value(&self.characteristics.event.id)
-            .await?;
+        let data = self.transport.read(&self.characteristics.event).await?;
 
         String::from_utf8(data).map_err(Into::into)
     }
 
     /// Subscribe for [Event::Event] events.
     pub async fn subscribe_events(&self) -> Result<(), Error> {
-        self.session
-            .start_notify(&self.characteristics.event.id)
-            .await
-            .map_err(Into::into)
+        self.transport
+            .subscribe(&self.characteristics.event)
+            .await?;
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .insert(Subscription::Event);
+        Ok(())
     }
 
     /// The facet currently facing up.
     pub async fn facet(&self) -> Result<Facet, Error> {
-        let data = self
-            .session
-            .read_characteristic_value(&self.characteristics.facet.id)
-            .await?;
+        let data = self.transport.read(&self.characteristics.facet).await?;
 
         match data.first() {
             Some(facet) => Ok(Facet::new(usize::from(*facet))?),
@@ -243,188 +488,230 @@ impl TimeFlip {
 
     /// Subscribe for [Event::Facet] events.
     pub async fn subscribe_facet(&self) -> Result<(), Error> {
-        self.session
-            .start_notify(&self.characteristics.facet.id)
-            .await
-            .map_err(Into::into)
+        self.transport
+            .subscribe(&self.characteristics.facet)
+            .await?;
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .insert(Subscription::Facet);
+        Ok(())
     }
 
     /// Subscribe for [Event::DoubleTap] events.
     pub async fn subscribe_double_tap(&self) -> Result<(), Error> {
-        self.session
-            .start_notify(&self.characteristics.double_tap.id)
-            .await
-            .map_err(Into::into)
+        self.transport
+            .subscribe(&self.characteristics.double_tap)
+            .await?;
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .insert(Subscription::DoubleTap);
+        Ok(())
     }
 
-    /// Write a command to TimeFlip2, check its execution and read its output from the
-    /// CommandResult characteristic.
-    async fn command<T>(
-        &self,
-        command: gatt::Command,
-    ) -> Result<<T as gatt::CommandResult>::Output, Error>
+    /// Write a command to TimeFlip2, wait for and correlate its result, and retry once with a
+    /// freshly written password if TimeFlip2 reports that the password is missing or incorrect.
+    async fn execute<R>(&self, command: gatt::Command) -> Result<R::Output, Error>
     where
-        T: gatt::CommandResult,
-        Error: From<T::Error>,
+        R: gatt::CommandResult,
+        Error: From<R::Error>,
     {
-        self.session
-            .write_characteristic_value(&self.characteristics.command.id, command.to_vec())
-            .await?;
-        let cmd_execution = self
-            .session
-            .read_characteristic_value(&self.characteristics.command.id)
+        match self.execute_once::<R>(&command).await {
+            Err(Error::PasswordRequired) => {
+                log::warn!("TimeFlip2 requires the password, re-writing and retrying command");
+                self.write_password().await?;
+                self.execute_once::<R>(&command).await
+            }
+            result => result,
+        }
+    }
+
+    /// Write `command`, check its execution status, and read its result from the CommandResult
+    /// characteristic, bounded by [COMMAND_TIMEOUT].
+    async fn execute_once<R>(&self, command: &gatt::Command) -> Result<R::Output, Error>
+    where
+        R: gatt::CommandResult,
+        Error: From<R::Error>,
+    {
+        self.transport
+            .write(&self.characteristics.command, command.to_vec())
             .await?;
-        if cmd_execution.len() < 2 || cmd_execution[0] != command.id() || cmd_execution[1] != 2 {
+
+        let cmd_execution = timeout(
+            COMMAND_TIMEOUT,
+            self.transport.read(&self.characteristics.command),
+        )
+        .await
+        .map_err(|_| Error::CommandTimeout)??;
+        if cmd_execution.len() < 2 || cmd_execution[0] != command.id() {
             return Err(Error::CommandExecutionFailed);
         }
+        match cmd_execution[1] {
+            command_status::SUCCESS => {}
+            command_status::PASSWORD_ERROR => return Err(Error::PasswordRequired),
+            _ => return Err(Error::CommandExecutionFailed),
+        }
 
-        let data = self
-            .session
-            .read_characteristic_value(&self.characteristics.command_result.id)
-            .await?;
-        T::from_data(data.as_slice()).map_err(Into::into)
+        let data = timeout(COMMAND_TIMEOUT, self.read_matching_result(command.id()))
+            .await
+            .map_err(|_| Error::CommandTimeout)??;
+        R::from_data(data.as_slice()).map_err(Into::into)
+    }
+
+    /// Poll the CommandResult characteristic, discarding any stale result left behind by a
+    /// previous command, until one whose leading byte matches `id` shows up.
+    async fn read_matching_result(&self, id: u8) -> Result<Vec<u8>, Error> {
+        loop {
+            let data = self
+                .transport
+                .read(&self.characteristics.command_result)
+                .await?;
+            match data.first() {
+                Some(first) if *first == id => return Ok(data),
+                _ => {
+                    log::debug!("discarding stale command result");
+                    tokio::time::sleep(COMMAND_POLL_INTERVAL).await;
+                }
+            }
+        }
     }
 
     /// Get the current time (in UTC) saved on TimeFlip2.
     pub async fn time(&self) -> Result<DateTime<Utc>, Error> {
-        self.command::<DateTime<Utc>>(gatt::Command::GetTime).await
+        self.execute::<DateTime<Utc>>(gatt::Command::GetTime).await
     }
 
     /// Set the time (in UTC) saved on TimeFlip2.
     pub async fn set_time(&self, time: DateTime<Utc>) -> Result<(), Error> {
-        self.command::<()>(gatt::Command::Time(time)).await
+        self.execute::<()>(gatt::Command::Time(time)).await
     }
 
     /// Get the system status of the TimeFlip2.
     pub async fn system_status(&self) -> Result<SystemStatus, Error> {
-        self.command::<SystemStatus>(gatt::Command::ReadStatus)
+        self.execute::<SystemStatus>(gatt::Command::ReadStatus)
             .await
     }
 
     /// Set the brightness of the TimeFlip2's LED.
     pub async fn brightness(&self, value: Percent) -> Result<(), Error> {
         log::info!("writing brightness {value} to TimeFlip2");
-        self.command::<()>(gatt::Command::Brightness(value)).await
+        self.execute::<()>(gatt::Command::Brightness(value)).await
     }
 
     /// Set the blink interval of the TimeFlip2's LED.
     pub async fn blink_interval(&self, value: BlinkInterval) -> Result<(), Error> {
         log::info!("writing blink interval {value} to TimeFlip2");
-        self.command::<()>(gatt::Command::BlinkInterval(value))
+        self.execute::<()>(gatt::Command::BlinkInterval(value))
             .await
     }
 
     /// Set the color of a facet's LED.
     pub async fn color(&self, facet: Facet, color: Color) -> Result<(), Error> {
         log::info!("writing color of facet {facet}: {color}");
-        self.command::<()>(gatt::Command::SetColor { facet, color })
+        self.execute::<()>(gatt::Command::SetColor { facet, color })
             .await
     }
 
     /// Set a facet's task.
     pub async fn task(&self, facet: Facet, task: FacetTask) -> Result<(), Error> {
         log::info!("writing task of facet {facet}: {task}");
-        self.command::<()>(gatt::Command::SetTaskParameter(facet, task))
+        self.execute::<()>(gatt::Command::SetTaskParameter(facet, task))
             .await
     }
 
     /// Get a facet's task.
     pub async fn get_task(&self, facet: Facet) -> Result<FacetSettings, Error> {
-        self.command::<FacetSettings>(gatt::Command::GetTaskParameter(facet))
+        self.execute::<FacetSettings>(gatt::Command::GetTaskParameter(facet))
+            .await
+    }
+
+    /// Reset all facets' tasks.
+    pub async fn reset_tasks(&self) -> Result<(), Error> {
+        log::info!("resetting all tasks on TimeFlip2");
+        self.execute::<()>(gatt::Command::ResetTasks).await
+    }
+
+    /// Enable or disable pausing by double-tapping the TimeFlip2.
+    pub async fn set_double_tap(&self, on: bool) -> Result<(), Error> {
+        log::info!("setting double-tap to {on}");
+        self.execute::<()>(gatt::Command::SetDoubleTap(on)).await
+    }
+
+    /// Read whether pausing by double-tapping the TimeFlip2 is enabled.
+    pub async fn double_tap(&self) -> Result<bool, Error> {
+        self.execute::<bool>(gatt::Command::ReadDoubleTap).await
+    }
+
+    /// Set the password TimeFlip2 requires commands to be authenticated with.
+    ///
+    /// Unlike [TimeFlip::write_password], which only authenticates the current session, this
+    /// changes the password stored on the device itself; it must then be passed to
+    /// [TimeFlip::connect] on future connections.
+    pub async fn set_password(&self, password: [u8; 6]) -> Result<(), Error> {
+        log::info!("setting a new password on TimeFlip2");
+        self.execute::<()>(gatt::Command::SetPassword(password))
             .await
     }
 
+    /// Reset the TimeFlip2 to its factory settings.
+    ///
+    /// TimeFlip2 will report [SyncType::FactoryReset] afterwards until it has been brought back
+    /// in sync, see [TimeFlip::sync].
+    pub async fn factory_reset(&self) -> Result<(), Error> {
+        log::warn!("resetting TimeFlip2 to factory settings");
+        self.execute::<()>(gatt::Command::FactoryReset).await
+    }
+
     /// Put the TimeFlip2 into lock mode.
     pub async fn lock(&self) -> Result<(), Error> {
         log::info!("locking TimeFlip2");
-        self.command::<()>(gatt::Command::LockMode(true)).await
+        self.execute::<()>(gatt::Command::LockMode(true)).await
     }
 
     /// Release the TimeFlip2 from lock mode.
     pub async fn unlock(&self) -> Result<(), Error> {
         log::info!("unlocking TimeFlip2");
-        self.command::<()>(gatt::Command::LockMode(false)).await
+        self.execute::<()>(gatt::Command::LockMode(false)).await
     }
 
     /// Put the TimeFlip2 into pause mode.
     pub async fn pause(&self) -> Result<(), Error> {
         log::info!("pausing TimeFlip2");
-        self.command::<()>(gatt::Command::PauseMode(true)).await
+        self.execute::<()>(gatt::Command::PauseMode(true)).await
     }
 
     /// Release the TimeFlip2 from pause mode.
     pub async fn unpause(&self) -> Result<(), Error> {
         log::info!("unpausing TimeFlip2");
-        self.command::<()>(gatt::Command::PauseMode(false)).await
+        self.execute::<()>(gatt::Command::PauseMode(false)).await
     }
 
     /// Set the TimeFlip2's auto pause time.
     pub async fn auto_pause(&self, time: Minutes) -> Result<(), Error> {
         log::info!("writing auto pause after {time} to TimeFlip2");
-        self.command::<()>(gatt::Command::AutoPauseTime(time)).await
+        self.execute::<()>(gatt::Command::AutoPauseTime(time)).await
     }
 
     /// Get the TimeFlip2's sync state.
     pub async fn sync_state(&self) -> Result<SyncState, Error> {
         let data = self
-            .session
-            .read_characteristic_value(&self.characteristics.system_state.id)
+            .transport
+            .read(&self.characteristics.system_state)
             .await?;
         SyncState::from_data(&data).map_err(Into::into)
     }
 
     /// Synchronize the TimeFlip2 to the given config.
     ///
-    /// Please note that this will not apply the configuration unconditionally, but only if
-    /// TimeFlip requires synchronization. When attempting to apply configuration use
-    /// [TimeFlip::set_config()] instead.
-    pub async fn sync(&self, config: &Config) -> Result<(), Error> {
-        let mut last_sync = None;
-        loop {
-            let sync_state = self.sync_state().await?;
-            if sync_state.accelerometer_error {
-                return Err(Error::AccelerometerError);
-            }
-            if sync_state.flash_error {
-                return Err(Error::FlashError);
-            }
-
-            if let Some(last_sync) = last_sync {
-                if last_sync == sync_state.sync {
-                    return Err(Error::SyncError(last_sync));
-                }
-            }
-
-            use SyncType::*;
-            match sync_state.sync {
-                FactoryReset | Time => {
-                    self.set_time(Utc::now()).await?;
-                }
-                FacetColor => {
-                    for (i, side) in config.sides.iter().enumerate() {
-                        let facet = Facet::new(i + 1)?;
-                        self.color(facet, side.color.clone()).await?;
-                    }
-                }
-                LedBrightness => {
-                    self.brightness(config.brightness.clone()).await?;
-                }
-                BlinkInterval => {
-                    self.blink_interval(config.blink_interval.clone()).await?;
-                }
-                TaskParameters => {
-                    for (i, side) in config.sides.iter().enumerate() {
-                        let facet = Facet::new(i + 1)?;
-                        self.task(facet, side.task.clone()).await?;
-                    }
-                }
-                AutoPause => {
-                    self.auto_pause(config.auto_pause.clone()).await?;
-                }
-                Synchronized => return Ok(()),
-            }
-            last_sync = Some(sync_state.sync);
-        }
+    /// Please note that this will not apply the configuration unconditionally, but only
+    /// whichever setting TimeFlip2 itself reports as out of sync. When attempting to apply
+    /// configuration unconditionally use [TimeFlip::write_config()] instead.
+    ///
+    /// Returns [SyncWarnings] for hardware errors TimeFlip2 reported along the way; these do not
+    /// stop synchronization but are worth surfacing to the caller.
+    pub async fn sync(&self, config: &Config) -> Result<SyncWarnings, Error> {
+        Synchronizer::new(config).run(self).await
     }
 
     /// Apply the given configuration to TimeFlip2's memory.
@@ -442,6 +729,26 @@ impl TimeFlip {
         Ok(())
     }
 
+    /// Read back as much of TimeFlip2's current configuration as its GATT interface exposes.
+    ///
+    /// TimeFlip2 does not offer a way to read back brightness, blink interval, the per-facet
+    /// colors/names or the password; those fields are filled in with [Config::default]'s values.
+    /// Only the auto pause time and each facet's assigned task are actually read from the device.
+    pub async fn read_config(&self) -> Result<Config, Error> {
+        let status = self.system_status().await?;
+        let mut config = Config {
+            auto_pause: status.auto_pause_time,
+            ..Config::default()
+        };
+
+        for side in config.sides.iter_mut() {
+            let settings = self.get_task(side.facet.clone()).await?;
+            side.task = settings.task;
+        }
+
+        Ok(config)
+    }
+
     /// Read a single history event identified by its ID.
     ///
     /// When `0xFFFFFFFF` is passed as `id`, the last event is returned.
@@ -449,13 +756,10 @@ impl TimeFlip {
         let mut read_command = Vec::with_capacity(5);
         read_command.put_u8(0x01);
         read_command.put_u32(id);
-        self.session
-            .write_characteristic_value(&self.characteristics.history.id, read_command)
-            .await?;
-        let data = self
-            .session
-            .read_characteristic_value(&self.characteristics.history.id)
+        self.transport
+            .write(&self.characteristics.history, read_command)
             .await?;
+        let data = self.transport.read(&self.characteristics.history).await?;
 
         Ok(Entry::from_data(&data)?)
     }
@@ -465,79 +769,28 @@ impl TimeFlip {
         self.read_history_entry(0xFFFF_FFFF).await
     }
 
-    /// Read history entries.
+    /// Read history entries newer than `id`.
     ///
     /// Please note that TimeFlip2 will only consider events with a duration of more than 5
     /// seconds.
     pub async fn read_history_since(&self, id: u32) -> Result<Vec<Entry>, Error> {
-        self.session
-            .start_notify(&self.characteristics.history.id)
-            .await?;
-        let mut stream = self
-            .session
-            .characteristic_event_stream(&self.characteristics.history.id)
-            .await?;
-
-        let mut read_command = Vec::with_capacity(5);
-        read_command.put_u8(0x02);
-        read_command.put_u32(id);
-        self.session
-            .write_characteristic_value(&self.characteristics.history.id, read_command)
-            .await?;
-
-        let mut entries = vec![];
-        while let Some(event) = stream.next().await {
-            match event {
-                BluetoothEvent::Characteristic {
-                    id,
-                    event: CharacteristicEvent::Value { value },
-                } => {
-                    if id != self.characteristics.history.id {
-                        return Err(Error::InvalidCharacteristicData(format!(
-                            "wrong ID in bluetooth event {:?}",
-                            id
-                        )));
-                    }
-                    match Entry::from_data(&value) {
-                        Ok(entry) => {
-                            log::debug!("new entry: {entry}");
-                            entries.push(entry);
-                        }
-                        Err(gatt::EntryError::EndOfHistory) => break,
-                        Err(e) => log::error!("skipping unparsable history event: {e}"),
-                    }
-                }
-                _ => {
-                    return Err(Error::InvalidCharacteristicData(format!(
-                        "invalid bluetooth event {:?}",
-                        event
-                    )))
-                }
-            }
-        }
-
-        self.session
-            .stop_notify(&self.characteristics.history.id)
-            .await?;
-
-        Ok(entries)
+        HistoryReader::new(id).read(self).await
     }
 
     /// Get a stream of events from TimeFlip2.
-    pub async fn event_stream(&self) -> Result<BoxStream<'_, Event>, Error> {
+    pub async fn event_stream(&self) -> Result<BoxStream<'static, Event>, Error> {
         let handles = gatt::EventHandles {
-            device_id: self.device.id.clone(),
-            battery_level: self.characteristics.battery_level.id.clone(),
-            last_event: self.characteristics.event.id.clone(),
-            facet: self.characteristics.facet.id.clone(),
-            double_tap: self.characteristics.double_tap.id.clone(),
+            battery_level: self.characteristics.battery_level.clone(),
+            last_event: self.characteristics.event.clone(),
+            facet: self.characteristics.facet.clone(),
+            double_tap: self.characteristics.double_tap.clone(),
         };
 
         Ok(self
-            .session
-            .device_event_stream(&self.device.id)
+            .transport
+            .event_stream(&self.device)
             .await?
-            .map(move |bt_event| gatt::Event::from_bluetooth_event(bt_event, &handles))
+            .map(move |event| gatt::Event::from_transport_event(event, &handles))
             .filter_map(|res| async move {
                 match res {
                     Ok(event) => Some(event),
@@ -550,3 +803,15 @@ impl TimeFlip {
             .boxed())
     }
 }
+
+/// [Transport]s that support tearing down the underlying connection explicitly.
+pub trait DisconnectableTransport: Transport {
+    /// Disconnect the given device.
+    async fn disconnect(&self, device: &Self::DeviceHandle) -> Result<(), Self::Error>;
+}
+
+impl DisconnectableTransport for BlueZTransport {
+    async fn disconnect(&self, device: &bluez_async::DeviceId) -> Result<(), BluetoothError> {
+        self.session().disconnect(device).await
+    }
+}
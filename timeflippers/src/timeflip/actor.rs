@@ -0,0 +1,281 @@
+//! Serializes GATT command traffic through a single task owning the [TimeFlip].
+//!
+//! [TimeFlip]'s methods each perform multiple sequential characteristic round trips (write
+//! Command, read Command to confirm execution, read CommandResult). If two futures issue
+//! commands concurrently on the same [TimeFlip], their reads and writes can interleave on the
+//! shared characteristics and the confirmation/result bytes get mismatched. [TimeFlipHandle] moves
+//! the [TimeFlip] into a background task and funnels every call through an `mpsc` channel, each
+//! carrying a `oneshot` reply sender, so the task processes requests strictly one at a time. This
+//! mirrors the message-passing/actor design Fuchsia's bt-gap and the Android Bluetooth stack use
+//! to guard shared mutable BLE state.
+use chrono::{DateTime, Utc};
+use futures::{future::BoxFuture, stream::BoxStream};
+use tokio::sync::{mpsc, oneshot};
+
+use super::{
+    BatteryAlert, BlinkInterval, BlueZTransport, Color, Config, Entry, Error, Event, Facet,
+    FacetSettings, FacetTask, Minutes, Percent, SyncState, SyncWarnings, SystemStatus, Threshold,
+    TimeFlip, TimeFlipDeviceInfo, Transport,
+};
+
+/// Capacity of the channel feeding requests to the background task.
+const CHANNEL_CAPACITY: usize = 32;
+
+/// A boxed, type-erased request queued for the background task: given exclusive access to the
+/// owned [TimeFlip], run some work on it and report the result back through its own (already
+/// captured) `oneshot` sender.
+type Job<T> = Box<dyn for<'a> FnOnce(&'a mut TimeFlip<T>) -> BoxFuture<'a, ()> + Send>;
+
+/// Run the owned [TimeFlip], processing queued requests one at a time until every
+/// [TimeFlipHandle] has been dropped.
+async fn run<T: Transport>(mut timeflip: TimeFlip<T>, mut jobs: mpsc::Receiver<Job<T>>) {
+    while let Some(job) = jobs.recv().await {
+        job(&mut timeflip).await;
+    }
+}
+
+/// A cheap, `Clone`-able handle to a [TimeFlip] owned by a background task.
+///
+/// Every method mirrors one of [TimeFlip]'s and is a thin wrapper sending the equivalent request
+/// to the owning task, so any number of [TimeFlipHandle] clones can safely share one dice: the
+/// task never runs two requests concurrently, so commands can no longer interleave.
+#[derive(Clone)]
+pub struct TimeFlipHandle<T: Transport = BlueZTransport> {
+    jobs: mpsc::Sender<Job<T>>,
+}
+
+impl<T: Transport> TimeFlipHandle<T>
+where
+    Error: From<T::Error>,
+{
+    /// Move `timeflip` into a background task and return a handle to it.
+    pub fn spawn(timeflip: TimeFlip<T>) -> Self {
+        let (jobs, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        tokio::spawn(run(timeflip, receiver));
+        TimeFlipHandle { jobs }
+    }
+
+    /// Send `f` to the owning task and await its result.
+    async fn call<R, F>(&self, f: F) -> Result<R, Error>
+    where
+        R: Send + 'static,
+        F: for<'a> FnOnce(&'a mut TimeFlip<T>) -> BoxFuture<'a, R> + Send + 'static,
+    {
+        let (reply, response) = oneshot::channel();
+        let job: Job<T> = Box::new(move |timeflip| {
+            Box::pin(async move {
+                let _ = reply.send(f(timeflip).await);
+            })
+        });
+
+        self.jobs.send(job).await.map_err(|_| Error::ActorStopped)?;
+        response.await.map_err(|_| Error::ActorStopped)
+    }
+
+    /// See [TimeFlip::device_info].
+    pub async fn device_info(&self) -> Result<TimeFlipDeviceInfo, Error> {
+        self.call(|timeflip| Box::pin(timeflip.device_info())).await
+    }
+
+    /// See [TimeFlip::battery_level].
+    pub async fn battery_level(&self) -> Result<Percent, Error> {
+        self.call(|timeflip| Box::pin(timeflip.battery_level()))
+            .await
+    }
+
+    /// See [TimeFlip::subscribe_battery_level].
+    pub async fn subscribe_battery_level(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.subscribe_battery_level()))
+            .await
+    }
+
+    /// See [TimeFlip::battery_watcher].
+    pub async fn battery_watcher(
+        &self,
+        thresholds: Vec<Threshold>,
+    ) -> Result<BoxStream<'static, BatteryAlert>, Error> {
+        self.call(move |timeflip| Box::pin(timeflip.battery_watcher(thresholds)))
+            .await
+    }
+
+    /// See [TimeFlip::last_event].
+    pub async fn last_event(&self) -> Result<String, Error> {
+        self.call(|timeflip| Box::pin(timeflip.last_event())).await
+    }
+
+    /// See [TimeFlip::subscribe_events].
+    pub async fn subscribe_events(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.subscribe_events()))
+            .await
+    }
+
+    /// See [TimeFlip::facet].
+    pub async fn facet(&self) -> Result<Facet, Error> {
+        self.call(|timeflip| Box::pin(timeflip.facet())).await
+    }
+
+    /// See [TimeFlip::subscribe_facet].
+    pub async fn subscribe_facet(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.subscribe_facet()))
+            .await
+    }
+
+    /// See [TimeFlip::subscribe_double_tap].
+    pub async fn subscribe_double_tap(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.subscribe_double_tap()))
+            .await
+    }
+
+    /// See [TimeFlip::time].
+    pub async fn time(&self) -> Result<DateTime<Utc>, Error> {
+        self.call(|timeflip| Box::pin(timeflip.time())).await
+    }
+
+    /// See [TimeFlip::set_time].
+    pub async fn set_time(&self, time: DateTime<Utc>) -> Result<(), Error> {
+        self.call(move |timeflip| Box::pin(timeflip.set_time(time)))
+            .await
+    }
+
+    /// See [TimeFlip::system_status].
+    pub async fn system_status(&self) -> Result<SystemStatus, Error> {
+        self.call(|timeflip| Box::pin(timeflip.system_status()))
+            .await
+    }
+
+    /// See [TimeFlip::brightness].
+    pub async fn brightness(&self, value: Percent) -> Result<(), Error> {
+        self.call(move |timeflip| Box::pin(timeflip.brightness(value)))
+            .await
+    }
+
+    /// See [TimeFlip::blink_interval].
+    pub async fn blink_interval(&self, value: BlinkInterval) -> Result<(), Error> {
+        self.call(move |timeflip| Box::pin(timeflip.blink_interval(value)))
+            .await
+    }
+
+    /// See [TimeFlip::color].
+    pub async fn color(&self, facet: Facet, color: Color) -> Result<(), Error> {
+        self.call(move |timeflip| Box::pin(timeflip.color(facet, color)))
+            .await
+    }
+
+    /// See [TimeFlip::task].
+    pub async fn task(&self, facet: Facet, task: FacetTask) -> Result<(), Error> {
+        self.call(move |timeflip| Box::pin(timeflip.task(facet, task)))
+            .await
+    }
+
+    /// See [TimeFlip::get_task].
+    pub async fn get_task(&self, facet: Facet) -> Result<FacetSettings, Error> {
+        self.call(move |timeflip| Box::pin(timeflip.get_task(facet)))
+            .await
+    }
+
+    /// See [TimeFlip::reset_tasks].
+    pub async fn reset_tasks(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.reset_tasks())).await
+    }
+
+    /// See [TimeFlip::set_double_tap].
+    pub async fn set_double_tap(&self, on: bool) -> Result<(), Error> {
+        self.call(move |timeflip| Box::pin(timeflip.set_double_tap(on)))
+            .await
+    }
+
+    /// See [TimeFlip::double_tap].
+    pub async fn double_tap(&self) -> Result<bool, Error> {
+        self.call(|timeflip| Box::pin(timeflip.double_tap())).await
+    }
+
+    /// See [TimeFlip::set_password].
+    pub async fn set_password(&self, password: [u8; 6]) -> Result<(), Error> {
+        self.call(move |timeflip| Box::pin(timeflip.set_password(password)))
+            .await
+    }
+
+    /// See [TimeFlip::factory_reset].
+    pub async fn factory_reset(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.factory_reset()))
+            .await
+    }
+
+    /// See [TimeFlip::lock].
+    pub async fn lock(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.lock())).await
+    }
+
+    /// See [TimeFlip::unlock].
+    pub async fn unlock(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.unlock())).await
+    }
+
+    /// See [TimeFlip::pause].
+    pub async fn pause(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.pause())).await
+    }
+
+    /// See [TimeFlip::unpause].
+    pub async fn unpause(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.unpause())).await
+    }
+
+    /// See [TimeFlip::auto_pause].
+    pub async fn auto_pause(&self, time: Minutes) -> Result<(), Error> {
+        self.call(move |timeflip| Box::pin(timeflip.auto_pause(time)))
+            .await
+    }
+
+    /// See [TimeFlip::sync_state].
+    pub async fn sync_state(&self) -> Result<SyncState, Error> {
+        self.call(|timeflip| Box::pin(timeflip.sync_state())).await
+    }
+
+    /// See [TimeFlip::sync].
+    pub async fn sync(&self, config: &Config) -> Result<SyncWarnings, Error> {
+        let config = config.clone();
+        self.call(move |timeflip| Box::pin(async move { timeflip.sync(&config).await }))
+            .await
+    }
+
+    /// See [TimeFlip::write_config].
+    pub async fn write_config(&self, config: Config) -> Result<(), Error> {
+        self.call(move |timeflip| Box::pin(timeflip.write_config(config)))
+            .await
+    }
+
+    /// See [TimeFlip::read_config].
+    pub async fn read_config(&self) -> Result<Config, Error> {
+        self.call(|timeflip| Box::pin(timeflip.read_config())).await
+    }
+
+    /// See [TimeFlip::read_history_entry].
+    pub async fn read_history_entry(&self, id: u32) -> Result<Entry, Error> {
+        self.call(move |timeflip| Box::pin(timeflip.read_history_entry(id)))
+            .await
+    }
+
+    /// See [TimeFlip::read_last_history_entry].
+    pub async fn read_last_history_entry(&self) -> Result<Entry, Error> {
+        self.call(|timeflip| Box::pin(timeflip.read_last_history_entry()))
+            .await
+    }
+
+    /// See [TimeFlip::read_history_since].
+    pub async fn read_history_since(&self, id: u32) -> Result<Vec<Entry>, Error> {
+        self.call(move |timeflip| Box::pin(timeflip.read_history_since(id)))
+            .await
+    }
+
+    /// See [TimeFlip::event_stream].
+    pub async fn event_stream(&self) -> Result<BoxStream<'static, Event>, Error> {
+        self.call(|timeflip| Box::pin(timeflip.event_stream()))
+            .await
+    }
+
+    /// See [TimeFlip::reconnect].
+    pub async fn reconnect(&self) -> Result<(), Error> {
+        self.call(|timeflip| Box::pin(timeflip.reconnect())).await
+    }
+}
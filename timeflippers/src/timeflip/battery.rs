@@ -0,0 +1,84 @@
+//! Debounced battery-threshold watching, built on top of [Event::BatteryLevel] notifications.
+use futures::stream::{BoxStream, StreamExt};
+
+use super::{Event, Percent};
+
+/// A named battery level to watch for crossings, e.g. `Threshold { name: "low", level: 20% }`.
+#[derive(Debug, Clone)]
+pub struct Threshold {
+    /// A short, human-readable label for this threshold, e.g. `"low"` or `"critical"`.
+    pub name: String,
+    /// The level at which this threshold is considered crossed.
+    pub level: Percent,
+}
+
+/// Which way a [Threshold] was crossed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// The battery level dropped to or below the threshold.
+    Falling,
+    /// The battery level rose back above the threshold.
+    Rising,
+}
+
+/// Emitted by [watch] when the battery level crosses a configured [Threshold].
+#[derive(Debug, Clone)]
+pub struct BatteryAlert {
+    /// The battery level that triggered the alert.
+    pub level: Percent,
+    /// The threshold that was crossed.
+    pub crossed: Threshold,
+    /// Whether the level was falling through or rising back past `crossed`.
+    pub direction: Direction,
+}
+
+/// Every [Threshold] crossed going from `previous` to `current`, in the order they were
+/// configured.
+fn crossings(previous: u8, current: u8, thresholds: &[Threshold]) -> Vec<BatteryAlert> {
+    thresholds
+        .iter()
+        .filter_map(|threshold| {
+            let level = threshold.level.get();
+            let direction = if previous > level && current <= level {
+                Some(Direction::Falling)
+            } else if previous <= level && current > level {
+                Some(Direction::Rising)
+            } else {
+                None
+            };
+
+            direction.map(|direction| BatteryAlert {
+                level: Percent::new(current.into()).expect("TimeFlip2 reported a valid percent"),
+                crossed: threshold.clone(),
+                direction,
+            })
+        })
+        .collect()
+}
+
+/// Debounce `events` down to [Event::BatteryLevel] changes and emit a [BatteryAlert] whenever one
+/// of `thresholds` is crossed, rather than on every notification.
+pub fn watch(
+    events: BoxStream<'static, Event>,
+    thresholds: Vec<Threshold>,
+) -> BoxStream<'static, BatteryAlert> {
+    events
+        .filter_map(|event| async move {
+            match event {
+                Event::BatteryLevel(percent) => Some(percent),
+                _ => None,
+            }
+        })
+        .scan(None, move |previous: &mut Option<u8>, percent| {
+            let current = percent.get();
+            let alerts = match *previous {
+                Some(previous) if previous != current => crossings(previous, current, &thresholds),
+                Some(_) => Vec::new(),
+                None => Vec::new(),
+            };
+            *previous = Some(current);
+            async move { Some(alerts) }
+        })
+        .flat_map(|alerts| futures::stream::iter(alerts))
+        .boxed()
+}
@@ -0,0 +1,157 @@
+//! Connection lifecycle management with automatic reconnect and re-subscription.
+use std::{collections::HashSet, time::Duration};
+
+use futures::stream::{BoxStream, StreamExt};
+
+use super::{BlueZTransport, Error, Event, Subscription, TimeFlip, Transport};
+
+/// Initial delay before the first reconnect attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound on the backoff delay between reconnect attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Stage of a [Connection]'s lifecycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Not connected, possibly waiting out a backoff before the next attempt.
+    Disconnected,
+    /// Establishing the underlying Bluetooth link, re-resolving characteristics, re-writing the
+    /// password and re-enabling notifications; all of these happen in this one state.
+    Connecting,
+    /// Link is up and notifications are re-enabled; the event stream is being opened.
+    Connected,
+    /// Subscribed and streaming events.
+    Ready,
+}
+
+/// Keeps a [TimeFlip] connected, transparently reconnecting on disconnect.
+///
+/// [Connection] owns one [TimeFlip] (built from the transport, device id, password and
+/// subscriptions of whichever [TimeFlip] it was created from) for its entire lifetime. On a
+/// dropped link it drives that same [TimeFlip] through [TimeFlip::reconnect] in place, so
+/// [Connection::timeflip] keeps returning a handle with live, freshly-resolved characteristics
+/// instead of a stale one. Repeated failures back off exponentially up to [MAX_BACKOFF], so a
+/// TimeFlip2 that is out of range does not get hammered with connection attempts.
+pub struct Connection<T: Transport = BlueZTransport> {
+    transport: T,
+    device: T::DeviceHandle,
+    password: Option<[u8; 6]>,
+    subscriptions: HashSet<Subscription>,
+    state: ConnectionState,
+    current: Option<TimeFlip<T>>,
+    inner: Option<BoxStream<'static, Event>>,
+    backoff: Duration,
+}
+
+impl<T: Transport> Connection<T>
+where
+    Error: From<T::Error>,
+{
+    /// Manage the connection of an already-connected [TimeFlip], reusing its transport, device
+    /// id, password and active subscriptions on every reconnect.
+    pub fn new(timeflip: &TimeFlip<T>) -> Self {
+        Connection {
+            transport: timeflip.transport.clone(),
+            device: timeflip.device.clone(),
+            password: Some(timeflip.password),
+            subscriptions: timeflip.subscriptions.lock().unwrap().clone(),
+            state: ConnectionState::Disconnected,
+            current: None,
+            inner: None,
+            backoff: INITIAL_BACKOFF,
+        }
+    }
+
+    /// The connection's current lifecycle state.
+    pub fn state(&self) -> ConnectionState {
+        self.state
+    }
+
+    /// The currently connected [TimeFlip], if a link has ever been established.
+    ///
+    /// This is the same instance [Connection] reconnects in place, so its characteristics are
+    /// always up to date even after the underlying link dropped and came back.
+    pub fn timeflip(&self) -> Option<&TimeFlip<T>> {
+        self.current.as_ref()
+    }
+
+    /// Connect the transport and get a fresh event stream, reusing the previously connected
+    /// [TimeFlip] (re-resolving its characteristics and re-subscribing in place) if there is one,
+    /// or connecting and subscribing a new one otherwise.
+    async fn reconnect(&mut self) -> Result<BoxStream<'static, Event>, Error> {
+        self.state = ConnectionState::Connecting;
+
+        match &mut self.current {
+            Some(timeflip) => timeflip.reconnect().await?,
+            None => {
+                self.transport.connect(&self.device).await?;
+                let timeflip = TimeFlip::from_transport(
+                    self.transport.clone(),
+                    self.device.clone(),
+                    self.password,
+                )
+                .await?;
+                for subscription in &self.subscriptions {
+                    match subscription {
+                        Subscription::BatteryLevel => timeflip.subscribe_battery_level().await?,
+                        Subscription::Event => timeflip.subscribe_events().await?,
+                        Subscription::Facet => timeflip.subscribe_facet().await?,
+                        Subscription::DoubleTap => timeflip.subscribe_double_tap().await?,
+                    }
+                }
+                self.current = Some(timeflip);
+            }
+        }
+
+        self.state = ConnectionState::Connected;
+        let stream = self
+            .current
+            .as_ref()
+            .expect("just connected above")
+            .event_stream()
+            .await?;
+        self.state = ConnectionState::Ready;
+        Ok(stream)
+    }
+
+    /// Await the connection's next event, transparently reconnecting (with backoff) on a dropped
+    /// link.
+    ///
+    /// This never ends on its own; a dropped link is retried behind the scenes, with
+    /// [Event::Disconnected] marking the gap for the caller. Use [Connection::timeflip] to read
+    /// from the currently-connected device between events.
+    pub async fn next_event(&mut self) -> Event {
+        let mut inner = match self.inner.take() {
+            Some(stream) => stream,
+            None => loop {
+                match self.reconnect().await {
+                    Ok(stream) => {
+                        self.backoff = INITIAL_BACKOFF;
+                        break stream;
+                    }
+                    Err(e) => {
+                        self.state = ConnectionState::Disconnected;
+                        log::warn!("reconnect failed: {e}, retrying in {:?}", self.backoff);
+                        tokio::time::sleep(self.backoff).await;
+                        self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+                    }
+                }
+            },
+        };
+
+        match inner.next().await {
+            Some(Event::Disconnected) => {
+                self.state = ConnectionState::Disconnected;
+                Event::Disconnected
+            }
+            Some(event) => {
+                self.inner = Some(inner);
+                event
+            }
+            None => {
+                self.state = ConnectionState::Disconnected;
+                Event::Disconnected
+            }
+        }
+    }
+}
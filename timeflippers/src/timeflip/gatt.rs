@@ -1,16 +1,15 @@
 //! Low level types for communicating with TimeFlip2 using BLE/GATT
 #![deny(missing_docs)]
 
-use bluez_async::{
-    uuid_from_u16, BluetoothError, BluetoothEvent, BluetoothSession, CharacteristicEvent,
-    CharacteristicId, CharacteristicInfo, DeviceEvent, DeviceId,
-};
 use bytes::{Buf, BufMut};
 use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
 use std::{convert::Infallible, fmt, num::TryFromIntError, string::FromUtf8Error, time::Duration};
 use thiserror::Error;
 use uuid::Uuid;
 
+use super::Transport;
+
 /// A GATT service.
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub enum Service {
@@ -18,6 +17,8 @@ pub enum Service {
     Battery,
     /// TimeFlip service.
     TimeFlip,
+    /// Standard GATT Device Information service.
+    DeviceInformation,
 }
 
 impl Service {
@@ -30,6 +31,7 @@ impl Service {
             TimeFlip => "F1196F50-71A4-11E6-BDF4-0800200C9A66"
                 .parse()
                 .expect("is a UUID"),
+            DeviceInformation => uuid_from_u16(0x180A),
         }
     }
 }
@@ -81,6 +83,22 @@ pub enum Characteristic {
     ///
     /// Supports Write, Read and Notify.
     History,
+    /// The device's manufacturer name, part of [Service::DeviceInformation].
+    ///
+    /// Can only be read from.
+    ManufacturerName,
+    /// The device's firmware revision, part of [Service::DeviceInformation].
+    ///
+    /// Can only be read from.
+    FirmwareRevision,
+    /// The device's hardware revision, part of [Service::DeviceInformation].
+    ///
+    /// Can only be read from.
+    HardwareRevision,
+    /// The device's serial number, part of [Service::DeviceInformation].
+    ///
+    /// Can only be read from.
+    SerialNumber,
 }
 
 impl Characteristic {
@@ -92,6 +110,9 @@ impl Characteristic {
             BatteryLevel => Service::Battery,
             Event | Facet | CommandResult | Command | DoubleTap | SystemState | Password
             | History => Service::TimeFlip,
+            ManufacturerName | FirmwareRevision | HardwareRevision | SerialNumber => {
+                Service::DeviceInformation
+            }
         }
     }
 
@@ -125,17 +146,21 @@ impl Characteristic {
             History => "F1196F58-71A4-11E6-BDF4-0800200C9A66"
                 .parse()
                 .expect("is a UUID"),
+            ManufacturerName => uuid_from_u16(0x2A29),
+            FirmwareRevision => uuid_from_u16(0x2A26),
+            HardwareRevision => uuid_from_u16(0x2A27),
+            SerialNumber => uuid_from_u16(0x2A25),
         }
     }
 
-    /// Query the characteristic's handle to be used by bluez.
-    pub async fn get_info(
+    /// Resolve the characteristic's handle on the given backend.
+    pub async fn get_info<T: Transport>(
         &self,
-        session: &BluetoothSession,
-        device: &DeviceId,
-    ) -> Result<CharacteristicInfo, BluetoothError> {
-        session
-            .get_service_characteristic_by_uuid(device, self.service().uuid(), self.uuid())
+        transport: &T,
+        device: &T::DeviceHandle,
+    ) -> Result<T::CharacteristicHandle, T::Error> {
+        transport
+            .resolve(device, self.service().uuid(), self.uuid())
             .await
     }
 }
@@ -172,9 +197,21 @@ pub enum Command {
     SetTaskParameter(super::Facet, FacetTask),
     /// Get the task parameter of a facet.
     GetTaskParameter(super::Facet),
-    // missing: Name Record (0x15, no idea what this actually does),
-    //          Set double-tap (0x16), Read double-tap (0x17), set password (0x30),
-    //          reset tasks (0xFE), factory reset (0xFF)
+    /// Enable or disable pausing by double-tapping the TimeFlip2.
+    SetDoubleTap(bool),
+    /// Read whether pausing by double-tapping the TimeFlip2 is enabled.
+    ReadDoubleTap,
+    /// Set the password TimeFlip2 requires commands to be authenticated with.
+    ///
+    /// Note that this is distinct from writing the password to
+    /// [Characteristic::Password](super::Characteristic::Password), which only authenticates the
+    /// current session; this command changes the password stored on the device itself.
+    SetPassword([u8; 6]),
+    /// Reset all facets' tasks.
+    ResetTasks,
+    /// Reset TimeFlip2 to its factory settings, see [SyncType::FactoryReset].
+    FactoryReset,
+    // missing: Name Record (0x15, no idea what this actually does)
 }
 
 impl Command {
@@ -193,6 +230,11 @@ impl Command {
             SetColor { .. } => 0x11,
             SetTaskParameter(_, _) => 0x13,
             GetTaskParameter(_) => 0x14,
+            SetDoubleTap(_) => 0x16,
+            ReadDoubleTap => 0x17,
+            SetPassword(_) => 0x30,
+            ResetTasks => 0xFE,
+            FactoryReset => 0xFF,
         }
     }
 
@@ -243,6 +285,15 @@ impl Command {
                 }
             }
             GetTaskParameter(facet) => data.put_u8(facet.0),
+            SetDoubleTap(on) => {
+                if *on {
+                    data.put_u8(0x01)
+                } else {
+                    data.put_u8(0x02)
+                }
+            }
+            ReadDoubleTap | ResetTasks | FactoryReset => {}
+            SetPassword(password) => data.put_slice(password),
         }
         data
     }
@@ -348,6 +399,53 @@ impl CommandResult for SystemStatus {
     }
 }
 
+/// Error for converting a [Characteristic::CommandResult]'s output to a double-tap setting.
+#[derive(Debug, Error)]
+pub enum DoubleTapError {
+    #[error("double-tap setting needs 2 bytes, read {0}")]
+    TooShort(usize),
+    #[error("invalid command in result: 0x{0:X}")]
+    InvalidCommand(u8),
+    #[error("unhandled double-tap value: 0x{0:X}")]
+    InvalidValue(u8),
+}
+
+impl CommandResult for bool {
+    type Output = Self;
+    type Error = DoubleTapError;
+
+    /// Construct whether double-tap is enabled from the data read from
+    /// [Characteristic::CommandResult].
+    fn from_data(mut data: &[u8]) -> Result<Self, DoubleTapError> {
+        if data.len() < 2 {
+            return Err(DoubleTapError::TooShort(data.len()));
+        }
+        let cmd = data.get_u8();
+        if cmd != Command::ReadDoubleTap.id() {
+            return Err(DoubleTapError::InvalidCommand(cmd));
+        }
+
+        match data.get_u8() {
+            1 => Ok(true),
+            2 => Ok(false),
+            v => Err(DoubleTapError::InvalidValue(v)),
+        }
+    }
+}
+
+/// TimeFlip2's identity, read from the standard Device Information service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// The device's manufacturer name.
+    pub manufacturer_name: String,
+    /// The device's firmware revision.
+    pub firmware_revision: String,
+    /// The device's hardware revision.
+    pub hardware_revision: String,
+    /// The device's serial number.
+    pub serial_number: String,
+}
+
 /// Task assigned to a facet.
 #[derive(Debug, PartialEq, Eq)]
 pub enum FacetTask {
@@ -489,6 +587,21 @@ impl SyncState {
             flash_error,
         })
     }
+
+    /// The synchronization currently required, if any.
+    pub fn sync(&self) -> &SyncType {
+        &self.sync
+    }
+
+    /// Whether TimeFlip2 reports an accelerometer error.
+    pub fn accelerometer_error(&self) -> bool {
+        self.accelerometer_error
+    }
+
+    /// Whether TimeFlip2 reports a flash error.
+    pub fn flash_error(&self) -> bool {
+        self.flash_error
+    }
 }
 
 /// Error when parsing a history entry.
@@ -506,7 +619,7 @@ pub enum EntryError {
 }
 
 /// An entry from TimeFlip2's history.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entry {
     /// ID of the entry.
     pub id: u32,
@@ -571,17 +684,11 @@ impl fmt::Display for Entry {
     }
 }
 
-/// Error while decoding bluetooth event
+/// Error while decoding a [TransportEvent](super::TransportEvent).
 #[derive(Debug, Error)]
-pub enum EventError {
-    #[error("unexpected bluetooth event stream: {0:?}")]
-    UnexpectedEvent(BluetoothEvent),
-    #[error("event for unexpected device in stream: {0:?}")]
-    UnexpectedDevice(DeviceId),
+pub enum EventError<H: fmt::Debug> {
     #[error("event for unexpected characteristic in stream: {0:?}")]
-    UnexpectedCharacteristic(CharacteristicId),
-    #[error("ignored connected event")]
-    IgnoreConnected,
+    UnexpectedCharacteristic(H),
     #[error("value too short for {0}")]
     TooShort(String),
     #[error("{0}")]
@@ -594,19 +701,22 @@ pub enum EventError {
     DoubleTap(super::FacetError),
 }
 
-/// Bluez handles for identifying Bluetooth events.
+/// Backend handles for identifying the characteristics subscribed to in an event stream.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct EventHandles {
-    pub device_id: DeviceId,
-    pub battery_level: CharacteristicId,
-    pub last_event: CharacteristicId,
-    pub facet: CharacteristicId,
-    pub double_tap: CharacteristicId,
+pub struct EventHandles<H> {
+    pub battery_level: H,
+    pub last_event: H,
+    pub facet: H,
+    pub double_tap: H,
 }
 
 /// Events for subscribed properties of the TimeFlip2.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Event {
+    /// Device has connected.
+    Connected,
+    /// The device's GATT services have been resolved and are ready to use.
+    ServicesResolved,
     /// Device has disconnected.
     Disconnected,
     /// Battery level has changed.
@@ -628,15 +738,15 @@ pub enum Event {
 }
 
 impl Event {
-    /// Construct an [Event] from a [BluetoothEvent].
-    pub fn from_bluetooth_event(
-        bt_event: BluetoothEvent,
-        handles: &EventHandles,
-    ) -> Result<Self, EventError> {
-        match bt_event {
-            BluetoothEvent::Characteristic {
-                id,
-                event: CharacteristicEvent::Value { value },
+    /// Construct an [Event] from a [TransportEvent](super::TransportEvent).
+    pub fn from_transport_event<H: PartialEq + fmt::Debug>(
+        transport_event: super::TransportEvent<H>,
+        handles: &EventHandles<H>,
+    ) -> Result<Self, EventError<H>> {
+        match transport_event {
+            super::TransportEvent::Value {
+                characteristic: id,
+                value,
             } => {
                 if id == handles.battery_level {
                     log::debug!("Battery Level event");
@@ -676,25 +786,9 @@ impl Event {
                     Err(EventError::UnexpectedCharacteristic(id))
                 }
             }
-            BluetoothEvent::Device {
-                id,
-                event: DeviceEvent::Connected { connected },
-            } => {
-                if id != handles.device_id {
-                    Err(EventError::UnexpectedDevice(id))
-                } else if connected {
-                    Err(EventError::IgnoreConnected)
-                } else {
-                    Ok(Event::Disconnected)
-                }
-            }
-            BluetoothEvent::Adapter { .. }
-            | BluetoothEvent::Device { .. }
-            | BluetoothEvent::Characteristic { .. } => {
-                // The adpter/device/characteristic events are marked as non-exhaustive, hence
-                // we have to have a catch all here.
-                Err(EventError::UnexpectedEvent(bt_event))
-            }
+            super::TransportEvent::Connected => Ok(Event::Connected),
+            super::TransportEvent::ServicesResolved => Ok(Event::ServicesResolved),
+            super::TransportEvent::Disconnected => Ok(Event::Disconnected),
         }
     }
 }
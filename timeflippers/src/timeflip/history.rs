@@ -0,0 +1,124 @@
+//! Streaming, resumable reader for TimeFlip2's history log.
+use std::collections::VecDeque;
+
+use bytes::BufMut;
+use futures::stream::{self, BoxStream, StreamExt};
+
+use super::{gatt, Error, TimeFlip, Transport, TransportEvent};
+
+/// Size in bytes of one history entry record.
+const ENTRY_SIZE: usize = 17;
+
+/// Paginates [Characteristic::History](super::gatt::Characteristic::History) from a saved
+/// watermark to [EntryError::EndOfHistory](gatt::EntryError::EndOfHistory), tracking the highest
+/// entry id seen so incremental syncs can resume where they left off.
+pub struct HistoryReader {
+    highest_id: u32,
+}
+
+impl HistoryReader {
+    /// Construct a [HistoryReader] that only reads entries newer than `since_id`.
+    pub fn new(since_id: u32) -> Self {
+        HistoryReader {
+            highest_id: since_id,
+        }
+    }
+
+    /// The highest entry id seen so far, usable as the next call's `since_id` watermark.
+    pub fn highest_id(&self) -> u32 {
+        self.highest_id
+    }
+
+    /// Read all entries newer than the reader's watermark, advancing it to the highest id seen.
+    pub async fn read<T: Transport>(
+        &mut self,
+        timeflip: &TimeFlip<T>,
+    ) -> Result<Vec<gatt::Entry>, Error>
+    where
+        Error: From<T::Error>,
+    {
+        let mut stream = Self::stream(self.highest_id, timeflip).await?;
+
+        let mut entries = vec![];
+        while let Some(entry) = stream.next().await {
+            self.highest_id = self.highest_id.max(entry.id);
+            entries.push(entry);
+        }
+
+        timeflip
+            .transport
+            .unsubscribe(&timeflip.characteristics.history)
+            .await?;
+
+        Ok(entries)
+    }
+
+    /// Subscribe to the history characteristic and stream entries newer than `since_id`.
+    ///
+    /// The stream decodes notifications that carry several concatenated 17-byte records into
+    /// individual entries, skips ones that fail to parse, and terminates cleanly once TimeFlip2
+    /// reports the end of its history or disconnects. The caller is responsible for unsubscribing
+    /// once done, e.g. via [HistoryReader::read].
+    pub async fn stream<T: Transport>(
+        since_id: u32,
+        timeflip: &TimeFlip<T>,
+    ) -> Result<BoxStream<'static, gatt::Entry>, Error>
+    where
+        Error: From<T::Error>,
+    {
+        timeflip
+            .transport
+            .subscribe(&timeflip.characteristics.history)
+            .await?;
+
+        let mut read_command = Vec::with_capacity(5);
+        read_command.put_u8(0x02);
+        read_command.put_u32(since_id);
+        timeflip
+            .transport
+            .write(&timeflip.characteristics.history, read_command)
+            .await?;
+
+        let events = timeflip.transport.event_stream(&timeflip.device).await?;
+        let history = timeflip.characteristics.history.clone();
+
+        let state = (events, history, VecDeque::new(), false);
+        Ok(stream::unfold(
+            state,
+            |(mut events, history, mut pending, mut done)| async move {
+                loop {
+                    if let Some(entry) = pending.pop_front() {
+                        return Some((entry, (events, history, pending, done)));
+                    }
+                    if done {
+                        return None;
+                    }
+
+                    match events.next().await {
+                        Some(TransportEvent::Value {
+                            characteristic,
+                            value,
+                        }) if characteristic == history => {
+                            for chunk in value.chunks(ENTRY_SIZE) {
+                                match gatt::Entry::from_data(chunk) {
+                                    Ok(entry) => pending.push_back(entry),
+                                    Err(gatt::EntryError::EndOfHistory) => {
+                                        done = true;
+                                        break;
+                                    }
+                                    Err(e) => {
+                                        log::error!("skipping unparsable history entry: {e}")
+                                    }
+                                }
+                            }
+                        }
+                        Some(TransportEvent::Disconnected) => done = true,
+                        Some(_) => continue,
+                        None => done = true,
+                    }
+                }
+            },
+        )
+        .boxed())
+    }
+}
@@ -0,0 +1,66 @@
+//! In-crate pairing/bonding over D-Bus, so headless deployments do not need `bluetoothctl`.
+//!
+//! `bluez_async` has no pairing support of its own, but it already talks to BlueZ over the
+//! system D-Bus session, and `org.bluez.Device1.Pair` is reachable over that same connection —
+//! the `bluer` crate demonstrates the same approach. Driving it here means a daemon can bond a
+//! new TimeFlip2 without shelling out to an interactive tool.
+use std::time::Duration;
+
+use bluez_async::{BluetoothSession, MacAddress};
+use thiserror::Error;
+use tokio::time::{sleep, timeout};
+
+/// Upper bound on how long to wait for BlueZ to report the device as paired, analogous to
+/// bt-gap's `HOST_INIT_TIMEOUT`.
+const PAIRING_TIMEOUT: Duration = Duration::from_secs(30);
+/// How often to poll the `Paired` property while a pairing attempt is in flight.
+const PAIRED_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Error bonding with a TimeFlip2 over D-Bus.
+#[derive(Error, Debug)]
+pub enum PairingError {
+    /// No device with the given MAC address is known to the adapter.
+    #[error("no device with MAC address {0} is known to the adapter")]
+    NotFound(MacAddress),
+    /// BlueZ never reported the device as paired within [PAIRING_TIMEOUT].
+    #[error("timed out waiting for pairing to complete")]
+    Timeout,
+    /// The D-Bus call to BlueZ failed.
+    #[error("D-Bus error while pairing: {0}")]
+    Dbus(#[from] zbus::Error),
+}
+
+/// Pair with, trust and bond the device at `mac_address`, driving `org.bluez.Device1.Pair`
+/// directly over the D-Bus connection `session` already holds.
+pub async fn pair(session: &BluetoothSession, mac_address: MacAddress) -> Result<(), PairingError> {
+    let device = session
+        .get_devices()
+        .await
+        .ok()
+        .into_iter()
+        .flatten()
+        .find(|dev| dev.mac_address == mac_address)
+        .ok_or(PairingError::NotFound(mac_address))?;
+
+    let proxy = zbus::Proxy::new(
+        session.connection(),
+        "org.bluez",
+        device.id.to_string(),
+        "org.bluez.Device1",
+    )
+    .await?;
+
+    proxy.call_method("Pair", &()).await?;
+    proxy.set_property("Trusted", true).await?;
+
+    timeout(PAIRING_TIMEOUT, async {
+        loop {
+            if proxy.get_property::<bool>("Paired").await.unwrap_or(false) {
+                return;
+            }
+            sleep(PAIRED_POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .map_err(|_| PairingError::Timeout)
+}
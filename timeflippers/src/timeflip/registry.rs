@@ -0,0 +1,89 @@
+//! Tracks multiple connected [TimeFlip]s, keyed by MAC address, and multiplexes their events.
+//!
+//! Mirrors the HostDispatcher pattern used by Fuchsia's bt-gap: rather than assuming a single
+//! adapter/device, a map of devices is tracked and their event streams are merged into one,
+//! tagged with the device that produced each event.
+use std::collections::HashMap;
+
+use futures::stream::{select_all, BoxStream, StreamExt};
+
+use super::{BlueZTransport, Error, Event, MacAddress, TimeFlip, Transport};
+
+/// An [Event] tagged with the MAC address of the [TimeFlip] that produced it.
+#[derive(Debug, Clone)]
+pub struct TaggedEvent {
+    /// The device the event originated from.
+    pub mac_address: MacAddress,
+    /// The event itself.
+    pub event: Event,
+}
+
+/// Holds multiple connected [TimeFlip]s, keyed by MAC address.
+///
+/// Use [TimeFlip::discover]/[TimeFlip::connect_to] to obtain the individual [TimeFlip]s, then
+/// [TimeFlipRegistry::insert] them here to fan their [TimeFlip::event_stream]s into a single
+/// [TaggedEvent] stream via [TimeFlipRegistry::merged_events].
+pub struct TimeFlipRegistry<T: Transport = BlueZTransport> {
+    devices: HashMap<MacAddress, TimeFlip<T>>,
+}
+
+impl<T: Transport> TimeFlipRegistry<T>
+where
+    Error: From<T::Error>,
+{
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        TimeFlipRegistry {
+            devices: HashMap::new(),
+        }
+    }
+
+    /// Add a connected device to the registry, returning the previous one at `mac_address`, if
+    /// any.
+    pub fn insert(
+        &mut self,
+        mac_address: MacAddress,
+        timeflip: TimeFlip<T>,
+    ) -> Option<TimeFlip<T>> {
+        self.devices.insert(mac_address, timeflip)
+    }
+
+    /// Remove and return a device from the registry.
+    pub fn remove(&mut self, mac_address: &MacAddress) -> Option<TimeFlip<T>> {
+        self.devices.remove(mac_address)
+    }
+
+    /// Get a reference to a registered device.
+    pub fn get(&self, mac_address: &MacAddress) -> Option<&TimeFlip<T>> {
+        self.devices.get(mac_address)
+    }
+
+    /// MAC addresses of every currently registered device.
+    pub fn mac_addresses(&self) -> impl Iterator<Item = &MacAddress> {
+        self.devices.keys()
+    }
+
+    /// Merge every registered device's event stream into a single [TaggedEvent] stream.
+    pub async fn merged_events(&self) -> Result<BoxStream<'static, TaggedEvent>, Error> {
+        let mut streams = Vec::with_capacity(self.devices.len());
+        for (mac_address, timeflip) in &self.devices {
+            let mac_address = mac_address.clone();
+            let stream = timeflip
+                .event_stream()
+                .await?
+                .map(move |event| TaggedEvent { mac_address, event });
+            streams.push(stream);
+        }
+
+        Ok(select_all(streams).boxed())
+    }
+}
+
+impl<T: Transport> Default for TimeFlipRegistry<T>
+where
+    Error: From<T::Error>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,130 @@
+//! Automatic resynchronization of a [TimeFlip] to a desired [Config].
+use chrono::Utc;
+
+use super::{
+    gatt::{SyncState, SyncType},
+    Error, TimeFlip, Transport,
+};
+use crate::{config::Config, types::Facet};
+
+/// Number of reconciliation steps attempted before giving up with [Error::SyncError].
+const MAX_ITERATIONS: usize = 16;
+
+/// Hardware conditions reported alongside a [SyncState] that do not stop synchronization, but
+/// are worth surfacing to the caller.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncWarnings {
+    /// TimeFlip2 reported an accelerometer error while synchronizing.
+    pub accelerometer_error: bool,
+    /// TimeFlip2 reported a flash error while synchronizing.
+    pub flash_error: bool,
+}
+
+/// Reconciles a [TimeFlip]'s [SyncState] against a desired [Config].
+///
+/// Every iteration reads the sync characteristic, maps the reported [SyncType] to the [Command]
+/// that clears it, issues that command, and reads the sync state again. This repeats until
+/// TimeFlip2 reports [SyncType::Synchronized]. It gives up with [Error::SyncError] as soon as
+/// the same dirty flag is reported twice in a row, or once [MAX_ITERATIONS] is reached, so a
+/// device that keeps reporting dirty flags cannot keep it cycling forever.
+///
+/// [Command]: super::gatt::Command
+pub struct Synchronizer<'a> {
+    config: &'a Config,
+}
+
+impl<'a> Synchronizer<'a> {
+    /// Construct a [Synchronizer] reconciling TimeFlip2 to `config`.
+    pub fn new(config: &'a Config) -> Self {
+        Synchronizer { config }
+    }
+
+    /// Run the reconciliation loop against `timeflip`.
+    pub async fn run<T: Transport>(&self, timeflip: &TimeFlip<T>) -> Result<SyncWarnings, Error>
+    where
+        Error: From<T::Error>,
+    {
+        let mut warnings = SyncWarnings::default();
+        let mut last_sync = None;
+
+        for _ in 0..MAX_ITERATIONS {
+            let sync_state = timeflip.sync_state().await?;
+            warnings.accelerometer_error |= sync_state.accelerometer_error();
+            warnings.flash_error |= sync_state.flash_error();
+
+            if Some(sync_state.sync()) == last_sync.as_ref() {
+                return Err(Error::SyncError(sync_state.sync().clone()));
+            }
+
+            if *sync_state.sync() == SyncType::Synchronized {
+                return Ok(warnings);
+            }
+
+            self.reconcile(timeflip, &sync_state).await?;
+            last_sync = Some(sync_state.sync().clone());
+        }
+
+        Err(Error::SyncError(last_sync.unwrap_or(SyncType::Synchronized)))
+    }
+
+    /// Issue the [Command] that addresses the reported [SyncType].
+    ///
+    /// [Command]: super::gatt::Command
+    async fn reconcile<T: Transport>(
+        &self,
+        timeflip: &TimeFlip<T>,
+        sync_state: &SyncState,
+    ) -> Result<(), Error>
+    where
+        Error: From<T::Error>,
+    {
+        use SyncType::*;
+        match sync_state.sync() {
+            Synchronized => Ok(()),
+            FactoryReset => self.push_all(timeflip).await,
+            Time => timeflip.set_time(Utc::now()).await,
+            FacetColor => self.push_colors(timeflip).await,
+            LedBrightness => timeflip.brightness(self.config.brightness.clone()).await,
+            BlinkInterval => timeflip.blink_interval(self.config.blink_interval.clone()).await,
+            TaskParameters => self.push_tasks(timeflip).await,
+            AutoPause => timeflip.auto_pause(self.config.auto_pause.clone()).await,
+        }
+    }
+
+    /// Push the full desired configuration, used on [SyncType::FactoryReset].
+    async fn push_all<T: Transport>(&self, timeflip: &TimeFlip<T>) -> Result<(), Error>
+    where
+        Error: From<T::Error>,
+    {
+        timeflip.set_time(Utc::now()).await?;
+        timeflip.brightness(self.config.brightness.clone()).await?;
+        timeflip
+            .blink_interval(self.config.blink_interval.clone())
+            .await?;
+        timeflip.auto_pause(self.config.auto_pause.clone()).await?;
+        self.push_colors(timeflip).await?;
+        self.push_tasks(timeflip).await
+    }
+
+    async fn push_colors<T: Transport>(&self, timeflip: &TimeFlip<T>) -> Result<(), Error>
+    where
+        Error: From<T::Error>,
+    {
+        for (i, side) in self.config.sides.iter().enumerate() {
+            let facet = Facet::new(i + 1)?;
+            timeflip.color(facet, side.color.clone()).await?;
+        }
+        Ok(())
+    }
+
+    async fn push_tasks<T: Transport>(&self, timeflip: &TimeFlip<T>) -> Result<(), Error>
+    where
+        Error: From<T::Error>,
+    {
+        for (i, side) in self.config.sides.iter().enumerate() {
+            let facet = Facet::new(i + 1)?;
+            timeflip.task(facet, side.task.clone()).await?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+//! Backend-agnostic Bluetooth transport used by [TimeFlip](super::TimeFlip).
+#![deny(missing_docs)]
+
+use futures::stream::BoxStream;
+use std::fmt;
+use uuid::Uuid;
+
+mod bluez;
+pub use bluez::BlueZTransport;
+
+#[cfg(feature = "btleplug")]
+mod btleplug_backend;
+#[cfg(feature = "btleplug")]
+pub use btleplug_backend::{BtleplugTransport, Error as BtleplugError};
+
+/// An event read from a [Transport]'s combined event stream.
+#[derive(Debug, Clone)]
+pub enum TransportEvent<H> {
+    /// A subscribed characteristic's value has changed.
+    Value {
+        /// The characteristic the value belongs to.
+        characteristic: H,
+        /// The new value.
+        value: Vec<u8>,
+    },
+    /// The device has connected.
+    Connected,
+    /// The device's GATT services have been resolved and are ready to use.
+    ServicesResolved,
+    /// The device has disconnected.
+    Disconnected,
+}
+
+/// Abstracts the GATT operations TimeFlip needs over a concrete Bluetooth backend.
+///
+/// Implementing this trait for a new platform stack is enough to make [TimeFlip](super::TimeFlip)
+/// run on top of it; none of the `Command`/`Event`/`CommandResult` machinery needs to change.
+/// [BlueZTransport] wraps `bluez_async` (Linux/BlueZ), while a `btleplug`-based backend (enabled
+/// via the `btleplug` feature) additionally covers macOS and Windows, since `btleplug` already
+/// abstracts WinRT/CoreBluetooth/BlueZ behind one peripheral/characteristic API.
+pub trait Transport: Clone + Send + Sync + 'static {
+    /// Handle identifying a characteristic resolved through this backend.
+    type CharacteristicHandle: Clone + fmt::Debug + Eq + Send + Sync;
+    /// Handle identifying a device resolved through this backend.
+    type DeviceHandle: Clone + fmt::Debug + Eq + Send + Sync;
+    /// Error produced by this backend.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// (Re-)establish the underlying connection to a device, without resolving characteristics.
+    async fn connect(&self, device: &Self::DeviceHandle) -> Result<(), Self::Error>;
+
+    /// Resolve a characteristic by its service/characteristic UUID on the given device.
+    async fn resolve(
+        &self,
+        device: &Self::DeviceHandle,
+        service: Uuid,
+        characteristic: Uuid,
+    ) -> Result<Self::CharacteristicHandle, Self::Error>;
+
+    /// Read the current value of a characteristic.
+    async fn read(&self, characteristic: &Self::CharacteristicHandle) -> Result<Vec<u8>, Self::Error>;
+
+    /// Write a value to a characteristic.
+    async fn write(
+        &self,
+        characteristic: &Self::CharacteristicHandle,
+        value: Vec<u8>,
+    ) -> Result<(), Self::Error>;
+
+    /// Enable notifications for a characteristic.
+    async fn subscribe(&self, characteristic: &Self::CharacteristicHandle) -> Result<(), Self::Error>;
+
+    /// Disable notifications for a characteristic.
+    async fn unsubscribe(&self, characteristic: &Self::CharacteristicHandle) -> Result<(), Self::Error>;
+
+    /// Get a stream of [TransportEvent]s covering every subscribed characteristic of a device as
+    /// well as its connection status.
+    async fn event_stream(
+        &self,
+        device: &Self::DeviceHandle,
+    ) -> Result<BoxStream<'static, TransportEvent<Self::CharacteristicHandle>>, Self::Error>;
+}
@@ -0,0 +1,110 @@
+//! [Transport] implementation backed by `bluez_async`, i.e. BlueZ on Linux.
+
+use bluez_async::{
+    BluetoothError, BluetoothEvent, BluetoothSession, CharacteristicEvent, CharacteristicId,
+    DeviceEvent, DeviceId,
+};
+use futures::stream::{BoxStream, StreamExt};
+use uuid::Uuid;
+
+use super::{Transport, TransportEvent};
+
+/// [Transport] backed by `bluez_async`.
+#[derive(Debug, Clone)]
+pub struct BlueZTransport {
+    session: BluetoothSession,
+}
+
+impl BlueZTransport {
+    /// Wrap an existing `bluez_async` session.
+    pub fn new(session: BluetoothSession) -> Self {
+        BlueZTransport { session }
+    }
+
+    /// The wrapped `bluez_async` session.
+    pub fn session(&self) -> &BluetoothSession {
+        &self.session
+    }
+}
+
+impl Transport for BlueZTransport {
+    type CharacteristicHandle = CharacteristicId;
+    type DeviceHandle = DeviceId;
+    type Error = BluetoothError;
+
+    async fn connect(&self, device: &DeviceId) -> Result<(), BluetoothError> {
+        self.session.connect(device).await
+    }
+
+    async fn resolve(
+        &self,
+        device: &DeviceId,
+        service: Uuid,
+        characteristic: Uuid,
+    ) -> Result<CharacteristicId, BluetoothError> {
+        self.session
+            .get_service_characteristic_by_uuid(device, service, characteristic)
+            .await
+            .map(|info| info.id)
+    }
+
+    async fn read(&self, characteristic: &CharacteristicId) -> Result<Vec<u8>, BluetoothError> {
+        self.session.read_characteristic_value(characteristic).await
+    }
+
+    async fn write(
+        &self,
+        characteristic: &CharacteristicId,
+        value: Vec<u8>,
+    ) -> Result<(), BluetoothError> {
+        self.session
+            .write_characteristic_value(characteristic, value)
+            .await
+    }
+
+    async fn subscribe(&self, characteristic: &CharacteristicId) -> Result<(), BluetoothError> {
+        self.session.start_notify(characteristic).await
+    }
+
+    async fn unsubscribe(&self, characteristic: &CharacteristicId) -> Result<(), BluetoothError> {
+        self.session.stop_notify(characteristic).await
+    }
+
+    async fn event_stream(
+        &self,
+        device: &DeviceId,
+    ) -> Result<BoxStream<'static, TransportEvent<CharacteristicId>>, BluetoothError> {
+        Ok(self
+            .session
+            .device_event_stream(device)
+            .await?
+            .filter_map(|event| async move {
+                match event {
+                    BluetoothEvent::Characteristic {
+                        id,
+                        event: CharacteristicEvent::Value { value },
+                    } => Some(TransportEvent::Value {
+                        characteristic: id,
+                        value,
+                    }),
+                    BluetoothEvent::Device {
+                        event: DeviceEvent::Connected { connected: true },
+                        ..
+                    } => Some(TransportEvent::Connected),
+                    BluetoothEvent::Device {
+                        event: DeviceEvent::Connected { connected: false },
+                        ..
+                    } => Some(TransportEvent::Disconnected),
+                    BluetoothEvent::Device {
+                        event:
+                            DeviceEvent::ServicesResolved {
+                                services_resolved: true,
+                            },
+                        ..
+                    } => Some(TransportEvent::ServicesResolved),
+                    _ => None,
+                }
+            })
+            .boxed())
+    }
+}
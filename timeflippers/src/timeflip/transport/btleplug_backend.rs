@@ -0,0 +1,157 @@
+//! [Transport] implementation backed by `btleplug`, covering macOS, Windows and Linux alike.
+//!
+//! `btleplug` already abstracts WinRT/CoreBluetooth/BlueZ behind one peripheral/characteristic
+//! API, so this backend is what lets [TimeFlip](crate::timeflip::TimeFlip) run on platforms other
+//! than Linux. Enabled via the `btleplug` feature.
+
+use btleplug::api::{
+    Central, Characteristic as BtleCharacteristic, Peripheral as _, ScanFilter, ValueNotification,
+    WriteType,
+};
+use btleplug::platform::{Manager, Peripheral};
+use futures::stream::{BoxStream, StreamExt};
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::{Transport, TransportEvent};
+
+/// Error produced by the [BtleplugTransport].
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Error produced by `btleplug` itself.
+    #[error("{0}")]
+    Btleplug(#[from] btleplug::Error),
+    /// No characteristic with the requested UUID was discovered on the peripheral.
+    #[error("characteristic {0} not found")]
+    CharacteristicNotFound(Uuid),
+    /// No adapter is available on this system.
+    #[error("no bluetooth adapter available")]
+    NoAdapter,
+    /// No peripheral advertising the TimeFlip service was found.
+    #[error("no peripheral advertising the requested service was found")]
+    NoPeripheral,
+}
+
+/// Handle identifying a characteristic resolved through [BtleplugTransport].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharacteristicHandle(BtleCharacteristic);
+
+/// [Transport] backed by `btleplug`.
+///
+/// Unlike [BlueZTransport](super::BlueZTransport), a `btleplug` peripheral already represents one
+/// connected device, so the device handle only carries the peripheral's identity.
+#[derive(Debug, Clone)]
+pub struct BtleplugTransport {
+    peripheral: Peripheral,
+}
+
+impl BtleplugTransport {
+    /// Scan for and connect to the first peripheral advertising `service`.
+    pub async fn connect(service: Uuid) -> Result<Self, Error> {
+        let manager = Manager::new().await?;
+        let adapter = manager
+            .adapters()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(Error::NoAdapter)?;
+
+        adapter
+            .start_scan(ScanFilter {
+                services: vec![service],
+            })
+            .await?;
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+
+        let peripheral = adapter
+            .peripherals()
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(Error::NoPeripheral)?;
+
+        peripheral.connect().await?;
+        peripheral.discover_services().await?;
+
+        Ok(BtleplugTransport { peripheral })
+    }
+
+    /// The identity of the connected peripheral, usable as this transport's device handle.
+    pub fn device_handle(&self) -> btleplug::api::PeripheralId {
+        self.peripheral.id()
+    }
+}
+
+impl Transport for BtleplugTransport {
+    type CharacteristicHandle = CharacteristicHandle;
+    type DeviceHandle = btleplug::api::PeripheralId;
+    type Error = Error;
+
+    async fn connect(&self, _device: &Self::DeviceHandle) -> Result<(), Error> {
+        Ok(self.peripheral.connect().await?)
+    }
+
+    async fn resolve(
+        &self,
+        _device: &Self::DeviceHandle,
+        _service: Uuid,
+        characteristic: Uuid,
+    ) -> Result<CharacteristicHandle, Error> {
+        self.peripheral
+            .characteristics()
+            .into_iter()
+            .find(|c| c.uuid == characteristic)
+            .map(CharacteristicHandle)
+            .ok_or(Error::CharacteristicNotFound(characteristic))
+    }
+
+    async fn read(&self, characteristic: &CharacteristicHandle) -> Result<Vec<u8>, Error> {
+        Ok(self.peripheral.read(&characteristic.0).await?)
+    }
+
+    async fn write(
+        &self,
+        characteristic: &CharacteristicHandle,
+        value: Vec<u8>,
+    ) -> Result<(), Error> {
+        Ok(self
+            .peripheral
+            .write(&characteristic.0, &value, WriteType::WithResponse)
+            .await?)
+    }
+
+    async fn subscribe(&self, characteristic: &CharacteristicHandle) -> Result<(), Error> {
+        Ok(self.peripheral.subscribe(&characteristic.0).await?)
+    }
+
+    async fn unsubscribe(&self, characteristic: &CharacteristicHandle) -> Result<(), Error> {
+        Ok(self.peripheral.unsubscribe(&characteristic.0).await?)
+    }
+
+    /// Only yields [TransportEvent::Value]s for subscribed characteristics; this backend does not
+    /// report [TransportEvent::Connected], [TransportEvent::ServicesResolved] or
+    /// [TransportEvent::Disconnected].
+    async fn event_stream(
+        &self,
+        _device: &Self::DeviceHandle,
+    ) -> Result<BoxStream<'static, TransportEvent<CharacteristicHandle>>, Error> {
+        let peripheral = self.peripheral.clone();
+        Ok(peripheral
+            .notifications()
+            .await?
+            .filter_map(move |ValueNotification { uuid, value }| {
+                let peripheral = peripheral.clone();
+                async move {
+                    peripheral
+                        .characteristics()
+                        .into_iter()
+                        .find(|c| c.uuid == uuid)
+                        .map(|c| TransportEvent::Value {
+                            characteristic: CharacteristicHandle(c),
+                            value,
+                        })
+                }
+            })
+            .boxed())
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{
     de::{self, Error},
-    Deserialize,
+    ser::{self, SerializeStruct},
+    Deserialize, Serialize,
 };
 use std::{default::Default, fmt};
 use thiserror::Error;
@@ -57,6 +58,15 @@ impl<'de> de::Deserialize<'de> for Percent {
     }
 }
 
+impl Serialize for Percent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_u8(self.0)
+    }
+}
+
 /// A type representing minutes.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Minutes(pub u16);
@@ -77,8 +87,45 @@ impl<'de> de::Deserialize<'de> for Minutes {
     }
 }
 
+impl Serialize for Minutes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_u16(self.0)
+    }
+}
+
+/// Error constructing a [Color] from a string.
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ColorError {
+    #[error("{0:?} is not a valid hex color (expected #RGB or #RRGGBB)")]
+    InvalidHex(String),
+    #[error("{0:?} is not a valid rgb() color (expected rgb(r,g,b) with components 0-255)")]
+    InvalidRgb(String),
+    #[error("{0:?} is not a known color name")]
+    UnknownName(String),
+}
+
+/// Named colors understood in addition to hex and `rgb()` notation.
+const NAMED_COLORS: &[(&str, (u8, u8, u8))] = &[
+    ("black", (0, 0, 0)),
+    ("white", (255, 255, 255)),
+    ("red", (255, 0, 0)),
+    ("green", (0, 255, 0)),
+    ("blue", (0, 0, 255)),
+    ("yellow", (255, 255, 0)),
+    ("cyan", (0, 255, 255)),
+    ("magenta", (255, 0, 255)),
+    ("orange", (255, 165, 0)),
+    ("purple", (128, 0, 128)),
+    ("gray", (128, 128, 128)),
+    ("grey", (128, 128, 128)),
+];
+
 /// Representation of the color of the LED
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Color {
     red: u16,
     green: u16,
@@ -95,6 +142,81 @@ impl Color {
     pub fn rgb(&self) -> (u16, u16, u16) {
         (self.red, self.green, self.blue)
     }
+
+    /// Get the Colors RGB value scaled down to 8 bits per component, e.g. for ANSI truecolor
+    /// escapes.
+    pub fn rgb8(&self) -> (u8, u8, u8) {
+        (
+            (self.red / 257) as u8,
+            (self.green / 257) as u8,
+            (self.blue / 257) as u8,
+        )
+    }
+
+    /// Construct a [Color] from its 8-bit RGB components, scaling each to 16 bits.
+    fn from_rgb8(red: u8, green: u8, blue: u8) -> Self {
+        // Scale by 257 (0x101) so 0xFF maps to 0xFFFF.
+        Color::from_rgb(
+            u16::from(red) * 257,
+            u16::from(green) * 257,
+            u16::from(blue) * 257,
+        )
+    }
+
+    /// Parse a color from `"#RGB"`/`"#RRGGBB"` hex notation, `"rgb(r,g,b)"` with 0-255
+    /// components, or a known color name (e.g. `"red"`, `"white"`).
+    pub fn parse(s: &str) -> Result<Self, ColorError> {
+        let s = s.trim();
+        if let Some(hex) = s.strip_prefix('#') {
+            return Self::parse_hex(hex).ok_or_else(|| ColorError::InvalidHex(s.to_string()));
+        }
+
+        if let Some(inner) = s.strip_prefix("rgb(").and_then(|s| s.strip_suffix(')')) {
+            return Self::parse_rgb(inner).ok_or_else(|| ColorError::InvalidRgb(s.to_string()));
+        }
+
+        NAMED_COLORS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+            .map(|(_, (r, g, b))| Color::from_rgb8(*r, *g, *b))
+            .ok_or_else(|| ColorError::UnknownName(s.to_string()))
+    }
+
+    fn parse_hex(hex: &str) -> Option<Self> {
+        let expand = |c: char| u8::from_str_radix(&format!("{c}{c}"), 16).ok();
+        let double = |a: &str| u8::from_str_radix(a, 16).ok();
+
+        let (r, g, b) = match hex.len() {
+            3 => {
+                let mut chars = hex.chars();
+                (
+                    expand(chars.next()?)?,
+                    expand(chars.next()?)?,
+                    expand(chars.next()?)?,
+                )
+            }
+            6 => (
+                double(&hex[0..2])?,
+                double(&hex[2..4])?,
+                double(&hex[4..6])?,
+            ),
+            _ => return None,
+        };
+
+        Some(Color::from_rgb8(r, g, b))
+    }
+
+    fn parse_rgb(inner: &str) -> Option<Self> {
+        let mut components = inner.split(',').map(|c| c.trim().parse::<u8>());
+        let r = components.next()?.ok()?;
+        let g = components.next()?.ok()?;
+        let b = components.next()?.ok()?;
+        if components.next().is_some() {
+            return None;
+        }
+
+        Some(Color::from_rgb8(r, g, b))
+    }
 }
 
 impl fmt::Display for Color {
@@ -104,6 +226,46 @@ impl fmt::Display for Color {
     }
 }
 
+impl<'de> de::Deserialize<'de> for Color {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Named(String),
+            Table { red: u16, green: u16, blue: u16 },
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Named(s) => Color::parse(&s).map_err(D::Error::custom),
+            Repr::Table { red, green, blue } => Ok(Color { red, green, blue }),
+        }
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let (r, g, b) = self.rgb();
+        // Hex notation can only round-trip colors whose components are an exact multiple of
+        // 257 (0xFFFF / 0xFF); fall back to the lossless 16-bit table for anything else, so
+        // writing back an already-loaded Config never mutates a device-synced color.
+        if r % 257 == 0 && g % 257 == 0 && b % 257 == 0 {
+            serializer.serialize_str(&format!("#{:02X}{:02X}{:02X}", r / 257, g / 257, b / 257))
+        } else {
+            let mut table = serializer.serialize_struct("Color", 3)?;
+            table.serialize_field("red", &r)?;
+            table.serialize_field("green", &g)?;
+            table.serialize_field("blue", &b)?;
+            table.end()
+        }
+    }
+}
+
 /// Error while constructing a [Facet].
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
@@ -157,8 +319,17 @@ impl<'de> de::Deserialize<'de> for Facet {
     }
 }
 
+impl Serialize for Facet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_u8(self.0)
+    }
+}
+
 /// Task assigned to a facet.
-#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum FacetTask {
     /// Simple counting up timer.
     Simple,
@@ -220,3 +391,12 @@ impl<'de> de::Deserialize<'de> for BlinkInterval {
         BlinkInterval::new(v).map_err(D::Error::custom)
     }
 }
+
+impl Serialize for BlinkInterval {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_u8(self.0)
+    }
+}
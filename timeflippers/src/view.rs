@@ -1,28 +1,53 @@
-use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
-use std::{collections::HashMap, fmt, time::Duration};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{self, Write},
+    time::Duration,
+};
 
 use crate::config::Config;
 use crate::timeflip::Entry;
+use crate::types::{Color, FacetTask};
 
 mod table;
 use table::{Position, TableHeader};
 
-struct DurationView<'a>(&'a Duration);
+mod range;
+pub use range::RangeError;
+
+/// Renders a [Duration] as `HH:MM:SS`, or as `HH:MM` rounded to the nearest whole minute
+/// (half up) when `rounded` is set. Each `DurationView` rounds independently, so a column of
+/// rounded rows is not guaranteed to sum to the same total as rounding the sum itself.
+struct DurationView<'a> {
+    duration: &'a Duration,
+    rounded: bool,
+}
 
 impl<'a> fmt::Display for DurationView<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let seconds = self.0.as_secs() % 60;
-        let minutes = (self.0.as_secs() / 60) % 60;
-        let hours = self.0.as_secs() / 3600;
-
-        let s = format!("{hours:02}:{minutes:02}:{seconds:02}");
-        f.pad(&s)
+        if self.rounded {
+            let total_minutes = (self.duration.as_secs() + 30) / 60;
+            let minutes = total_minutes % 60;
+            let hours = total_minutes / 60;
+
+            f.pad(&format!("{hours:02}:{minutes:02}"))
+        } else {
+            let seconds = self.duration.as_secs() % 60;
+            let minutes = (self.duration.as_secs() / 60) % 60;
+            let hours = self.duration.as_secs() / 3600;
+
+            f.pad(&format!("{hours:02}:{minutes:02}:{seconds:02}"))
+        }
     }
 }
 
 pub struct History {
     entries: Vec<Entry>,
     names: Vec<String>,
+    tasks: Vec<FacetTask>,
+    colors: Vec<Color>,
 }
 
 impl History {
@@ -41,6 +66,8 @@ impl History {
                     }
                 })
                 .collect(),
+            tasks: config.sides.iter().map(|side| side.task.clone()).collect(),
+            colors: config.sides.iter().map(|side| side.color.clone()).collect(),
         }
     }
 
@@ -48,6 +75,8 @@ impl History {
         HistoryFiltered {
             entries: self.entries.iter().collect(),
             names: &self.names,
+            tasks: &self.tasks,
+            colors: &self.colors,
         }
     }
 
@@ -59,25 +88,93 @@ impl History {
                 .filter(|entry| !entry.pause && entry.time > date)
                 .collect(),
             names: &self.names,
+            tasks: &self.tasks,
+            colors: &self.colors,
         }
     }
+
+    /// Filter to entries in `[start, end)`.
+    fn filter_range<'a>(&'a self, start: DateTime<Utc>, end: DateTime<Utc>) -> HistoryFiltered<'a> {
+        HistoryFiltered {
+            entries: self
+                .entries
+                .iter()
+                .filter(|entry| !entry.pause && entry.time >= start && entry.time < end)
+                .collect(),
+            names: &self.names,
+            tasks: &self.tasks,
+            colors: &self.colors,
+        }
+    }
+
+    /// Filter using a human-friendly relative date-range expression, e.g. `"today"`,
+    /// `"last week"`, `"this month"`, `"3 days ago"`, or a bare ISO date (`"2024-01-31"`).
+    pub fn range<'a>(&'a self, spec: &str) -> Result<HistoryFiltered<'a>, RangeError> {
+        let (start, end) = range::resolve(spec, Local::now())?;
+        Ok(self.filter_range(start, end))
+    }
+}
+
+/// How to bucket entries when grouping a [HistoryFiltered] by time, e.g. for
+/// [HistoryFiltered::table_by]/[HistoryFiltered::summarized_by].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Granularity {
+    /// One bucket per calendar day.
+    Day,
+    /// One bucket per ISO week, starting on Monday.
+    Week,
+    /// One bucket per calendar month.
+    Month,
+}
+
+/// The label for a group bucket starting on `date`, given the [Granularity] it was grouped by:
+/// the bare date for [Granularity::Day], a `start – end` range for [Granularity::Week], or
+/// `YYYY-MM` for [Granularity::Month].
+fn group_label(date: NaiveDate, granularity: Granularity) -> String {
+    match granularity {
+        Granularity::Day => date.to_string(),
+        Granularity::Week => {
+            let end = date.checked_add_days(Days::new(6)).expect("in range");
+            format!("{date} – {}", end.format("%m-%d"))
+        }
+        Granularity::Month => date.format("%Y-%m").to_string(),
+    }
+}
+
+/// An [Entry] together with the [Side](crate::config::Side) name/task it resolves to.
+pub struct ResolvedEntry<'a> {
+    /// The underlying history entry.
+    pub entry: &'a Entry,
+    /// The name of the facet the entry was logged on.
+    pub name: &'a str,
+    /// The task assigned to the facet the entry was logged on.
+    pub task: &'a FacetTask,
 }
 
 pub struct HistoryFiltered<'a> {
     entries: Vec<&'a Entry>,
     names: &'a [String],
+    tasks: &'a [FacetTask],
+    colors: &'a [Color],
 }
 
 impl<'a> HistoryFiltered<'a> {
-    fn group_by_day(&self) -> Vec<(NaiveDate, Vec<&Entry>)> {
+    /// Bucket entries by [Granularity], keyed by each bucket's start date.
+    fn group_by(&self, granularity: Granularity) -> Vec<(NaiveDate, Vec<&Entry>)> {
         let timezone = Local::now().timezone();
 
         let mut groups = HashMap::<NaiveDate, Vec<&Entry>>::new();
         for entry in &self.entries {
-            groups
-                .entry(entry.time.with_timezone(&timezone).date_naive())
-                .or_default()
-                .push(entry);
+            let date = entry.time.with_timezone(&timezone).date_naive();
+            let key = match granularity {
+                Granularity::Day => date,
+                Granularity::Week => date
+                    .checked_sub_days(Days::new(date.weekday().num_days_from_monday().into()))
+                    .expect("in range"),
+                Granularity::Month => date.with_day(1).expect("day 1 is always valid"),
+            };
+
+            groups.entry(key).or_default().push(entry);
         }
 
         let mut sorted = groups.into_iter().collect::<Vec<_>>();
@@ -89,25 +186,53 @@ impl<'a> HistoryFiltered<'a> {
         HistoryTable {
             groups: vec![(None, self.entries.clone())],
             names: self.names,
+            colors: self.colors,
+            rounded: false,
         }
     }
 
     pub fn table_by_day(&'a self) -> HistoryTable<'a> {
+        self.table_by(Granularity::Day)
+    }
+
+    /// Like [HistoryFiltered::table_by_day], but bucketed by the given [Granularity].
+    pub fn table_by(&'a self, granularity: Granularity) -> HistoryTable<'a> {
         let groups = self
-            .group_by_day()
+            .group_by(granularity)
             .into_iter()
-            .map(|(date, entries)| (Some(format!(" {} ", date)), entries))
+            .map(|(date, entries)| {
+                (
+                    Some(format!(" {} ", group_label(date, granularity))),
+                    entries,
+                )
+            })
             .collect();
 
         HistoryTable {
             groups,
             names: self.names,
+            colors: self.colors,
+            rounded: false,
         }
     }
 
+    /// Resolve each entry to the name/task of the facet it was logged on.
+    pub fn resolved(&self) -> impl Iterator<Item = ResolvedEntry<'a>> + '_ {
+        self.entries.iter().map(|&entry| ResolvedEntry {
+            entry,
+            name: &self.names[entry.facet.index_zero()],
+            task: &self.tasks[entry.facet.index_zero()],
+        })
+    }
+
     pub fn summarized(&self) -> Summarized {
+        self.summarized_by(Granularity::Day)
+    }
+
+    /// Like [HistoryFiltered::summarized], but bucketed by the given [Granularity].
+    pub fn summarized_by(&self, granularity: Granularity) -> Summarized {
         let groups = self
-            .group_by_day()
+            .group_by(granularity)
             .into_iter()
             .map(|(date, entries)| {
                 let mut durations = HashMap::<String, Duration>::new();
@@ -118,10 +243,147 @@ impl<'a> HistoryFiltered<'a> {
                     *sum = sum.saturating_add(entry.duration);
                 }
 
-                (date, durations)
+                (group_label(date, granularity), durations)
+            })
+            .collect();
+
+        let colors = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), effective_color(&self.colors[i], i)))
+            .collect();
+
+        Summarized {
+            groups,
+            colors,
+            rounded: false,
+        }
+    }
+
+    /// Sum each side's duration across the whole filtered range into a single breakdown, sorted
+    /// descending by duration.
+    pub fn totals(&self) -> Totals {
+        let mut durations = HashMap::<String, Duration>::new();
+        for entry in &self.entries {
+            let sum = durations
+                .entry(self.names[entry.facet.index_zero()].clone())
+                .or_default();
+            *sum = sum.saturating_add(entry.duration);
+        }
+
+        let total = durations.values().fold(Duration::ZERO, |acc, duration| {
+            acc.saturating_add(*duration)
+        });
+
+        let mut durations: Vec<_> = durations.into_iter().collect();
+        durations.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        Totals {
+            durations,
+            total,
+            rounded: false,
+        }
+    }
+
+    /// Build a [Report] of typed rows plus per-task and per-day aggregates, suitable for
+    /// machine-readable output.
+    pub fn report(&self) -> Report {
+        let timezone = Local::now().timezone();
+        let mut by_task = HashMap::<String, Duration>::new();
+        let mut by_day = HashMap::<NaiveDate, Duration>::new();
+
+        let rows = self
+            .resolved()
+            .map(|resolved| {
+                let start = resolved.entry.time.and_utc();
+                let day = start.with_timezone(&timezone).date_naive();
+                let task = resolved.task.to_string();
+
+                *by_task.entry(task.clone()).or_default() += resolved.entry.duration;
+                *by_day.entry(day).or_default() += resolved.entry.duration;
+
+                ReportRow {
+                    id: resolved.entry.id,
+                    facet: resolved.entry.facet.index(),
+                    name: resolved.name.to_string(),
+                    task,
+                    start,
+                    duration: resolved.entry.duration,
+                    day,
+                    pause: resolved.entry.pause,
+                }
             })
             .collect();
-        Summarized { groups }
+
+        Report {
+            rows,
+            by_task,
+            by_day,
+        }
+    }
+}
+
+/// A single logged session, resolved to the facet's name/task and bucketed by day, shaped for
+/// machine-readable export.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportRow {
+    /// ID of the underlying history entry.
+    pub id: u32,
+    /// Index of the facet the session was logged on.
+    pub facet: u8,
+    /// Name of the facet the session was logged on.
+    pub name: String,
+    /// Task assigned to the facet, rendered as text.
+    pub task: String,
+    /// When the session started.
+    pub start: DateTime<Utc>,
+    /// How long the session lasted.
+    pub duration: Duration,
+    /// The local calendar day the session started on.
+    pub day: NaiveDate,
+    /// Whether this entry marks a pause rather than an active session.
+    pub pause: bool,
+}
+
+/// Typed history rows plus per-task and per-day duration aggregates.
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    /// One row per logged session.
+    pub rows: Vec<ReportRow>,
+    /// Total duration per task.
+    pub by_task: HashMap<String, Duration>,
+    /// Total duration per local calendar day.
+    pub by_day: HashMap<NaiveDate, Duration>,
+}
+
+impl Report {
+    /// Render the raw rows as CSV, one row per session, with a header line.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("id,facet,name,task,start,duration_secs,day,pause\n");
+        for row in &self.rows {
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                row.id,
+                row.facet,
+                csv_field(&row.name),
+                csv_field(&row.task),
+                row.start.to_rfc3339(),
+                row.duration.as_secs(),
+                row.day,
+                row.pause,
+            ));
+        }
+        out
+    }
+}
+
+/// Quote a CSV field if it contains characters that would otherwise be ambiguous.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
     }
 }
 
@@ -184,13 +446,66 @@ where
     }
 }
 
+/// A readable palette cycled by facet index, used as a fallback side color when a side's
+/// [Color] is still [Color::default] (i.e. unset).
+const FALLBACK_PALETTE: &[(u8, u8, u8)] = &[
+    (230, 25, 75),
+    (60, 180, 75),
+    (255, 225, 25),
+    (0, 130, 200),
+    (245, 130, 48),
+    (145, 30, 180),
+    (70, 240, 240),
+    (240, 50, 230),
+    (210, 245, 60),
+    (250, 190, 212),
+    (0, 128, 128),
+    (220, 190, 255),
+];
+
+/// The color to render a side in: its configured [Color], or a [FALLBACK_PALETTE] entry picked
+/// by facet index if none was configured.
+fn effective_color(color: &Color, facet_index: usize) -> (u8, u8, u8) {
+    if *color == Color::default() {
+        FALLBACK_PALETTE[facet_index % FALLBACK_PALETTE.len()]
+    } else {
+        color.rgb8()
+    }
+}
+
+/// Wrap already-formatted `text` in a truecolor ANSI escape, resetting afterwards.
+fn ansi(rgb: (u8, u8, u8), text: &str) -> String {
+    let (r, g, b) = rgb;
+    format!("\x1b[38;2;{r};{g};{b}m{text}\x1b[0m")
+}
+
+/// Renders `T` with its side-name cells painted using per-side [Color]s (truecolor ANSI
+/// escapes), in contrast to `T`'s plain `Display` impl. Build one with e.g.
+/// [HistoryTable::colored] or [Summarized::colored]; pipe/non-TTY output should stick to the
+/// plain `Display` impl instead.
+pub struct Colored<T>(T);
+
+impl<T: RenderColored> fmt::Display for Colored<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_colored(f)
+    }
+}
+
+/// Implemented by views with a colorized rendering, wrapped by [Colored].
+pub trait RenderColored {
+    /// Render `self` with side-name cells painted in their [effective_color].
+    fn fmt_colored(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
 pub struct HistoryTable<'a> {
     groups: Vec<(Option<String>, Vec<&'a Entry>)>,
     names: &'a [String],
+    colors: &'a [Color],
+    rounded: bool,
 }
 
-impl<'a> fmt::Display for HistoryTable<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl<'a> HistoryTable<'a> {
+    fn render(&self, f: &mut fmt::Formatter<'_>, colored: bool) -> fmt::Result {
         const WIDTH_NAME: usize = 15;
         const WIDTH_STARTED: usize = 30;
         const WIDTH_DURATION: usize = 10;
@@ -209,15 +524,14 @@ impl<'a> fmt::Display for HistoryTable<'a> {
         )?;
 
         for (name, entries) in &self.groups {
-            write!(
-                f,
-                "{}",
-                GroupTable {
-                    group: name.as_deref(),
-                    entries: &entries[..],
-                    names: &self.names,
-                }
-            )?;
+            GroupTable {
+                group: name.as_deref(),
+                entries: &entries[..],
+                names: self.names,
+                colors: self.colors,
+                rounded: self.rounded,
+            }
+            .render(f, colored)?;
         }
 
         write!(
@@ -231,16 +545,41 @@ impl<'a> fmt::Display for HistoryTable<'a> {
 
         Ok(())
     }
+
+    /// Render with each side-name cell painted in its configured color (see [Colored]).
+    pub fn colored(self) -> Colored<Self> {
+        Colored(self)
+    }
+
+    /// Round each row's duration to the nearest whole minute instead of showing `HH:MM:SS`.
+    pub fn rounded(mut self) -> Self {
+        self.rounded = true;
+        self
+    }
+}
+
+impl<'a> fmt::Display for HistoryTable<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, false)
+    }
+}
+
+impl<'a> RenderColored for HistoryTable<'a> {
+    fn fmt_colored(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, true)
+    }
 }
 
 struct GroupTable<'a> {
     group: Option<&'a str>,
     entries: &'a [&'a Entry],
     names: &'a [String],
+    colors: &'a [Color],
+    rounded: bool,
 }
 
-impl<'a> fmt::Display for GroupTable<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl<'a> GroupTable<'a> {
+    fn render(&self, f: &mut fmt::Formatter<'_>, colored: bool) -> fmt::Result {
         let timezone = Local::now().timezone();
         const WIDTH_NAME: usize = 15;
         const WIDTH_STARTED: usize = 30;
@@ -262,17 +601,22 @@ impl<'a> fmt::Display for GroupTable<'a> {
         }
 
         for entry in self.entries {
+            let index = entry.facet.index_zero();
+            let color = colored.then(|| effective_color(&self.colors[index], index));
+
             writeln!(
                 f,
                 "│ {} │",
                 EntryTableView {
                     entry,
-                    name: &self.names[entry.facet.index_zero()],
+                    name: &self.names[index],
                     timezone: &timezone,
                     separator: "│",
                     width_name: WIDTH_NAME,
                     width_started: WIDTH_STARTED,
                     width_duration: WIDTH_DURATION,
+                    color,
+                    rounded: self.rounded,
                 },
             )?;
         }
@@ -281,6 +625,12 @@ impl<'a> fmt::Display for GroupTable<'a> {
     }
 }
 
+impl<'a> fmt::Display for GroupTable<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, false)
+    }
+}
+
 struct EntryTableView<'a, T: TimeZone> {
     entry: &'a Entry,
     name: &'a str,
@@ -290,6 +640,10 @@ struct EntryTableView<'a, T: TimeZone> {
     width_name: usize,
     width_started: usize,
     width_duration: usize,
+    /// Truecolor RGB to paint the name cell in, or `None` for plain text.
+    color: Option<(u8, u8, u8)>,
+    /// Round the duration to the nearest whole minute instead of showing `HH:MM:SS`.
+    rounded: bool,
 }
 
 impl<'a, T> fmt::Display for EntryTableView<'a, T>
@@ -298,14 +652,21 @@ where
     <T as TimeZone>::Offset: fmt::Display,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("{:<width_name$}", self.name, width_name = self.width_name);
+        let name = match self.color {
+            Some(rgb) => ansi(rgb, &name),
+            None => name,
+        };
+
         let line = format!(
-            "{:<width_name$}{}{:^width_started$}{}{:>width_duration$}",
-            self.name,
+            "{name}{}{:^width_started$}{}{:>width_duration$}",
             self.separator,
             self.entry.time.with_timezone(self.timezone).to_string(),
             self.separator,
-            DurationView(&self.entry.duration),
-            width_name = self.width_name,
+            DurationView {
+                duration: &self.entry.duration,
+                rounded: self.rounded,
+            },
             width_started = self.width_started,
             width_duration = self.width_duration,
         );
@@ -314,11 +675,25 @@ where
     }
 }
 
-pub struct Summarized {
-    groups: Vec<(NaiveDate, HashMap<String, Duration>)>,
+/// Per-side duration totals across a [HistoryFiltered]'s entire range, sorted descending by
+/// duration, built by [HistoryFiltered::totals].
+pub struct Totals {
+    durations: Vec<(String, Duration)>,
+    total: Duration,
+    rounded: bool,
 }
 
-impl fmt::Display for Summarized {
+impl Totals {
+    /// Round each row's duration (including the total row) to the nearest whole minute instead
+    /// of showing `HH:MM:SS`. Each row rounds independently, so the displayed total is not
+    /// guaranteed to equal the sum of the displayed rows.
+    pub fn rounded(mut self) -> Self {
+        self.rounded = true;
+        self
+    }
+}
+
+impl fmt::Display for Totals {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         const WIDTH_NAME: usize = 20;
         const WIDTH_DURATION: usize = 10;
@@ -332,23 +707,145 @@ impl fmt::Display for Summarized {
             },
         )?;
 
-        for (time, durations) in self.groups.iter() {
+        for (name, duration) in &self.durations {
+            writeln!(
+                f,
+                "│ {:<width_name$}│{:>width_duration$} │",
+                name,
+                DurationView {
+                    duration,
+                    rounded: self.rounded,
+                },
+                width_name = WIDTH_NAME,
+                width_duration = WIDTH_DURATION,
+            )?;
+        }
+
+        writeln!(
+            f,
+            "{}",
+            TableHeader {
+                columns: vec![("", WIDTH_NAME), ("", WIDTH_DURATION)],
+                position: Position::Center,
+            },
+        )?;
+
+        writeln!(
+            f,
+            "│ {:<width_name$}│{:>width_duration$} │",
+            "Total",
+            DurationView {
+                duration: &self.total,
+                rounded: self.rounded,
+            },
+            width_name = WIDTH_NAME,
+            width_duration = WIDTH_DURATION,
+        )?;
+
+        write!(
+            f,
+            "{}",
+            TableHeader {
+                columns: vec![("", WIDTH_NAME), ("", WIDTH_DURATION)],
+                position: Position::Bottom,
+            },
+        )
+    }
+}
+
+pub struct Summarized {
+    groups: Vec<(String, HashMap<String, Duration>)>,
+    colors: HashMap<String, (u8, u8, u8)>,
+    rounded: bool,
+}
+
+impl Summarized {
+    /// [Summarized::groups] with durations in seconds, so the shape round-trips exactly as JSON
+    /// (map keys must be strings).
+    fn export(&self) -> HashMap<String, HashMap<String, u64>> {
+        self.groups
+            .iter()
+            .map(|(group, durations)| {
+                let durations = durations
+                    .iter()
+                    .map(|(name, duration)| (name.clone(), duration.as_secs()))
+                    .collect();
+                (group.clone(), durations)
+            })
+            .collect()
+    }
+
+    /// Write the per-group side-name → duration-seconds breakdown as a nested JSON object.
+    pub fn to_json(&self, w: impl Write) -> serde_json::Result<()> {
+        serde_json::to_writer(w, &self.export())
+    }
+
+    /// Write the per-group side-name → duration-seconds breakdown as CSV, one row per
+    /// group/side pair.
+    pub fn to_csv(&self, mut w: impl Write) -> io::Result<()> {
+        writeln!(w, "group,side,duration_secs")?;
+        for (group, durations) in &self.groups {
+            for (name, duration) in durations {
+                writeln!(w, "{},{},{}", group, csv_field(name), duration.as_secs())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Render with each side-name cell painted in its configured color (see [Colored]).
+    pub fn colored(self) -> Colored<Self> {
+        Colored(self)
+    }
+
+    /// Round each duration to the nearest whole minute instead of showing `HH:MM:SS`. Each cell
+    /// rounds independently, so a column of rounded 0:30s may legitimately sum to more than any
+    /// individually rounded value.
+    pub fn rounded(mut self) -> Self {
+        self.rounded = true;
+        self
+    }
+
+    fn render(&self, f: &mut fmt::Formatter<'_>, colored: bool) -> fmt::Result {
+        const WIDTH_NAME: usize = 20;
+        const WIDTH_DURATION: usize = 10;
+
+        writeln!(
+            f,
+            "{}",
+            TableHeader {
+                columns: vec![(" Side ", WIDTH_NAME), (" Duration ", WIDTH_DURATION)],
+                position: Position::Top,
+            },
+        )?;
+
+        for (group, durations) in self.groups.iter() {
             writeln!(
                 f,
                 "{}",
                 TableHeader {
-                    columns: vec![(&time.to_string(), WIDTH_NAME), ("", WIDTH_DURATION)],
+                    columns: vec![(group.as_str(), WIDTH_NAME), ("", WIDTH_DURATION)],
                     position: Position::Center,
                 },
             )?;
 
             for (facet, duration) in durations.iter() {
+                let name = format!("{:<width_name$}", facet, width_name = WIDTH_NAME);
+                let name = if colored {
+                    self.colors
+                        .get(facet)
+                        .map(|&rgb| ansi(rgb, &name))
+                        .unwrap_or(name)
+                } else {
+                    name
+                };
+
                 writeln!(
                     f,
-                    "│ {:<width_name$}│{:>width_duration$} │",
-                    facet,
-                    DurationView(&duration),
-                    width_name = WIDTH_NAME,
+                    "│ {name}│{:>width_duration$} │",
+                    DurationView {
+                        duration,
+                        rounded: self.rounded,
+                    },
                     width_duration = WIDTH_DURATION,
                 )?;
             }
@@ -364,3 +861,15 @@ impl fmt::Display for Summarized {
         )
     }
 }
+
+impl fmt::Display for Summarized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, false)
+    }
+}
+
+impl RenderColored for Summarized {
+    fn fmt_colored(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, true)
+    }
+}
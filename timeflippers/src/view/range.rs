@@ -0,0 +1,117 @@
+//! Parses human-friendly relative date-range expressions into concrete `[start, end)` bounds.
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeZone, Utc};
+use thiserror::Error;
+
+/// Error parsing a date-range expression.
+#[derive(Debug, Error)]
+pub enum RangeError {
+    /// The expression did not match any recognized date/range keyword or ISO date.
+    #[error("unrecognized date range expression: {0:?}")]
+    Unrecognized(String),
+}
+
+/// Resolve `spec` against `now` into a `[start, end)` pair of local midnights, converted to UTC.
+///
+/// Recognizes bare ISO dates (`2024-01-31`), `today`, `yesterday`, `this week`/`last week`
+/// (weeks start on Monday), `this month`/`last month`, and `N days ago`/`N weeks ago`.
+pub fn resolve(
+    spec: &str,
+    now: DateTime<Local>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), RangeError> {
+    let today = now.date_naive();
+    let spec = spec.trim();
+
+    let (start, end) = match spec {
+        "today" => (today, succ(today)),
+        "yesterday" => (pred(today), today),
+        "this week" => {
+            let start = start_of_week(today);
+            (start, add_weeks(start, 1))
+        }
+        "last week" => {
+            let start = add_weeks(start_of_week(today), -1);
+            (start, add_weeks(start, 1))
+        }
+        "this month" => {
+            let start = start_of_month(today);
+            (start, next_month(start))
+        }
+        "last month" => {
+            let start_this_month = start_of_month(today);
+            (prev_month(start_this_month), start_this_month)
+        }
+        spec => {
+            if let Ok(date) = NaiveDate::parse_from_str(spec, "%Y-%m-%d") {
+                (date, succ(date))
+            } else if let Some(n) = parse_n_ago(spec, "days ago") {
+                let start = today - chrono::Duration::days(n);
+                (start, succ(start))
+            } else if let Some(n) = parse_n_ago(spec, "weeks ago") {
+                let start = add_weeks(start_of_week(today), -n);
+                (start, add_weeks(start, 1))
+            } else {
+                return Err(RangeError::Unrecognized(spec.to_string()));
+            }
+        }
+    };
+
+    Ok((to_utc(start), to_utc(end)))
+}
+
+/// Convert a local midnight to UTC.
+fn to_utc(date: NaiveDate) -> DateTime<Utc> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight is valid"))
+        .single()
+        .expect("midnight is not ambiguous under DST")
+        .with_timezone(&Utc)
+}
+
+fn succ(date: NaiveDate) -> NaiveDate {
+    date.checked_add_days(Days::new(1)).expect("in range")
+}
+
+fn pred(date: NaiveDate) -> NaiveDate {
+    date.checked_sub_days(Days::new(1)).expect("in range")
+}
+
+/// The Monday of the ISO week `date` falls in.
+fn start_of_week(date: NaiveDate) -> NaiveDate {
+    date.checked_sub_days(Days::new(date.weekday().num_days_from_monday().into()))
+        .expect("in range")
+}
+
+fn add_weeks(date: NaiveDate, weeks: i64) -> NaiveDate {
+    if weeks >= 0 {
+        date.checked_add_days(Days::new(weeks as u64 * 7))
+            .expect("in range")
+    } else {
+        date.checked_sub_days(Days::new((-weeks) as u64 * 7))
+            .expect("in range")
+    }
+}
+
+fn start_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("day 1 is always valid")
+}
+
+fn next_month(date: NaiveDate) -> NaiveDate {
+    if date.month() == 12 {
+        NaiveDate::from_ymd_opt(date.year() + 1, 1, 1).expect("valid date")
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1).expect("valid date")
+    }
+}
+
+fn prev_month(date: NaiveDate) -> NaiveDate {
+    if date.month() == 1 {
+        NaiveDate::from_ymd_opt(date.year() - 1, 12, 1).expect("valid date")
+    } else {
+        NaiveDate::from_ymd_opt(date.year(), date.month() - 1, 1).expect("valid date")
+    }
+}
+
+/// Parse `"N <suffix>"`, e.g. `parse_n_ago("3 days ago", "days ago") == Some(3)`.
+fn parse_n_ago(spec: &str, suffix: &str) -> Option<i64> {
+    spec.strip_suffix(suffix)?.trim().parse().ok()
+}
@@ -1,22 +1,25 @@
 use crossterm_027 as crossterm;
 use std::{
     cmp::max,
+    collections::BTreeMap,
     error::Error,
     io,
     path::{Path, PathBuf},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::bail;
-use chrono::Local;
+use arboard::Clipboard;
+use chrono::{Datelike, Days, Local, NaiveDate, NaiveDateTime};
 use clap::Parser;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use futures::StreamExt;
+use futures::{stream::BoxStream, StreamExt};
 use futures_timer::Delay;
+use notify::{Event as NotifyEvent, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, ListState},
@@ -24,14 +27,14 @@ use ratatui::{
 use serde::{Deserialize, Serialize};
 use std::collections::{
     hash_map::Entry::{Occupied, Vacant},
-    HashMap,
+    HashMap, HashSet,
 };
 use timeflippers::{
     timeflip::{Entry, Event as TimeEvent},
     view::DurationView,
     BluetoothSession, Config, Facet, TimeFlip,
 };
-use tokio::{fs, select};
+use tokio::{fs, sync::mpsc};
 use tui_textarea::{Input, Key, TextArea};
 
 struct StatefulList<T> {
@@ -103,10 +106,29 @@ impl<T> StatefulList<T> {
     }
 }
 
+/// First id handed out to a split-generated entry, well above any id a TimeFlip2 device can
+/// report (ids read off the device come from a `u32` counter that starts at 0), so split
+/// children never collide with a future `last_seen`.
+const SPLIT_ID_BASE: u32 = 0x8000_0000;
+
+/// A selected entry's start time and duration being adjusted in [State::EditingTime].
+struct TimeEdit {
+    id: u32,
+    time: NaiveDateTime,
+    duration: Duration,
+}
+
 struct App {
     items: StatefulList<u32>,
     entries: HashMap<u32, MyEntry>,
     show_invisible: bool,
+    report_granularity: ReportGranularity,
+    dirty: bool,
+    last_change: Instant,
+    query: Option<String>,
+    edited_ids: HashSet<u32>,
+    time_edit: Option<TimeEdit>,
+    next_split_id: u32,
 }
 
 impl App {
@@ -122,26 +144,135 @@ impl App {
                 }
             })
             .collect();
+        // Resume past any split children already persisted, so a restart doesn't reissue an id
+        // that is already in use and silently overwrite that entry.
+        let next_split_id = entries
+            .iter()
+            .map(|e| e.entry.id)
+            .filter(|&id| id >= SPLIT_ID_BASE)
+            .max()
+            .map_or(SPLIT_ID_BASE, |id| id + 1);
         App {
             items: StatefulList::with_items(entry_ids, None),
             entries: map,
             show_invisible: false,
+            report_granularity: ReportGranularity::Day,
+            dirty: false,
+            last_change: Instant::now(),
+            query: None,
+            edited_ids: HashSet::new(),
+            time_edit: None,
+            next_split_id,
+        }
+    }
+
+    /// Mark [App::entries] as having unsaved changes, resetting the auto-save idle timer.
+    fn mark_dirty(&mut self) {
+        self.dirty = true;
+        self.last_change = Instant::now();
+    }
+
+    /// Mark the entry `id` as edited since the last save, so a concurrent [App::merge_reloaded]
+    /// keeps the in-memory copy instead of overwriting it with the on-disk one.
+    fn mark_entry_dirty(&mut self, id: u32) {
+        self.edited_ids.insert(id);
+        self.mark_dirty();
+    }
+
+    /// Merge a freshly reloaded `on_disk` entry set into [App::entries]: for ids not in
+    /// [App::edited_ids], take the on-disk `description`/`visible`/`entry`; ids edited since the
+    /// last save keep their in-memory value so an external change never clobbers unsaved work.
+    fn merge_reloaded(&mut self, on_disk: Vec<MyEntry>) {
+        for reloaded in on_disk {
+            match self.entries.entry(reloaded.entry.id) {
+                Vacant(v) => {
+                    v.insert(reloaded);
+                }
+                Occupied(mut o) if !self.edited_ids.contains(&reloaded.entry.id) => {
+                    *o.get_mut() = reloaded;
+                }
+                Occupied(_) => {}
+            }
+        }
+    }
+
+    /// Allocate a fresh id for a split child, from the reserved [SPLIT_ID_BASE] range.
+    fn alloc_split_id(&mut self) -> u32 {
+        let id = self.next_split_id;
+        self.next_split_id += 1;
+        id
+    }
+
+    /// Whether ending `id`'s entry at `end_time` would overlap the entry that chronologically
+    /// follows it, i.e. the entry with the lowest start time after `id`'s own start time. Split
+    /// children (see [App::alloc_split_id]) are allocated ids far outside chronological order, so
+    /// this compares by entry time rather than id.
+    fn overlaps_next(&self, id: u32, end_time: NaiveDateTime) -> bool {
+        self.entries
+            .get(&id)
+            .map(|entry| entry.entry.time)
+            .and_then(|start_time| {
+                self.entries
+                    .values()
+                    .filter(|e| e.entry.id != id && e.entry.time > start_time)
+                    .min_by_key(|e| e.entry.time)
+            })
+            .is_some_and(|next| end_time > next.entry.time)
+    }
+
+    /// Split the entry `id` into two: it keeps `first_duration` starting at its existing start
+    /// time, and a newly allocated (see [App::alloc_split_id]) entry takes the remainder,
+    /// starting where the first leaves off. Both inherit the original's facet and description.
+    fn split_entry(&mut self, id: u32, first_duration: Duration) {
+        if let Some(original) = self.entries.get(&id) {
+            let second_duration = original.entry.duration.saturating_sub(first_duration);
+            let second_time = original.entry.time
+                + chrono::Duration::from_std(first_duration).expect("should work");
+
+            let mut second = original.clone();
+            second.entry.id = self.alloc_split_id();
+            second.entry.time = second_time;
+            second.entry.duration = second_duration;
+
+            let first = self.entries.get_mut(&id).expect("checked above");
+            first.entry.duration = first_duration;
+
+            let second_id = second.entry.id;
+            self.entries.insert(second_id, second);
+            self.mark_entry_dirty(id);
+            self.mark_entry_dirty(second_id);
         }
     }
 
-    fn update_entry_list(&mut self) {
+    /// Rebuild the visible entry list, keeping an entry only if it is visible (or
+    /// [App::show_invisible] is set) and, if [App::query] is set, the query is a substring of
+    /// its facet name or one of its description lines.
+    fn update_entry_list(&mut self, config: &Config) {
+        let query = self.query.as_deref().filter(|q| !q.is_empty());
         let mut new_items: Vec<u32> = self
             .entries
             .values()
             .filter_map(|e| {
-                if e.entry.duration > Duration::from_secs(30) {
-                    match (e.visible, self.show_invisible) {
-                        (true, _) | (false, true) => Some(e.entry.id),
-                        _ => None,
+                if e.entry.duration <= Duration::from_secs(30) {
+                    return None;
+                }
+                match (e.visible, self.show_invisible) {
+                    (true, _) | (false, true) => {}
+                    _ => return None,
+                }
+                if let Some(query) = query {
+                    let name_matches = facet_name(&e.entry.facet, config)
+                        .to_lowercase()
+                        .contains(query);
+                    let description_matches = e
+                        .description
+                        .iter()
+                        .any(|line| line.to_lowercase().contains(query));
+                    if !name_matches && !description_matches {
+                        return None;
                     }
-                } else {
-                    None
                 }
+                Some(e.entry.id)
             })
             .collect();
         new_items.sort();
@@ -153,9 +284,9 @@ impl App {
         self.items = StatefulList::with_items(new_items, selection);
     }
 
-    fn toggle_visibility(&mut self) {
+    fn toggle_visibility(&mut self, config: &Config) {
         self.show_invisible = !self.show_invisible;
-        self.update_entry_list();
+        self.update_entry_list(config);
     }
 }
 
@@ -166,6 +297,12 @@ struct Options {
     config: PathBuf,
     #[arg(help = "read events from and write new events to file")]
     persistent_file: PathBuf,
+    #[arg(
+        long,
+        help = "idle time in milliseconds before unsaved changes are auto-saved",
+        default_value = "30000"
+    )]
+    autosave_interval_ms: u64,
 }
 
 #[tokio::main]
@@ -234,6 +371,91 @@ async fn persist_history(persistent_file: &PathBuf, entries: &[MyEntry]) -> anyh
     Ok(())
 }
 
+/// Every input the main loop in [run] reacts to. Each variant is fed by its own task (see
+/// [forward_terminal_events], [forward_timeflip_events], [tick] and [autosave_ticker] below, plus
+/// [watch_persistent_file]'s `notify` callback), all sending into one channel, so `run` becomes a
+/// flat `while let Some(event) = rx.recv().await` dispatch instead of a multi-armed `select!`.
+enum LoopEvent {
+    /// An event from the TimeFlip2 device itself (double tap, facet change, ...).
+    TimeFlip(TimeEvent),
+    /// A terminal input event (key press, resize, ...).
+    Term(Event),
+    /// A redraw is due, independent of any other activity.
+    Tick,
+    /// Time to check whether unsaved changes have gone idle long enough to flush to disk.
+    AutosaveDue,
+    /// The persistent file changed on disk, e.g. edited by another tool or synced in.
+    FileChanged,
+    /// The Bluetooth session's background task exited; `run` should stop.
+    BgTaskExited(anyhow::Result<()>),
+}
+
+/// How often [LoopEvent::Tick] fires, driving the redraw cadence independently of input.
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// How often [LoopEvent::AutosaveDue] fires.
+const AUTOSAVE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Forward terminal input as [LoopEvent::Term] until the event stream ends or `tx` is dropped.
+async fn forward_terminal_events(tx: mpsc::UnboundedSender<LoopEvent>) {
+    let mut reader = EventStream::new();
+    while let Some(Ok(event)) = reader.next().await {
+        if tx.send(LoopEvent::Term(event)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Forward TimeFlip device events as [LoopEvent::TimeFlip] until the stream ends or `tx` is
+/// dropped.
+async fn forward_timeflip_events(
+    mut stream: BoxStream<'static, TimeEvent>,
+    tx: mpsc::UnboundedSender<LoopEvent>,
+) {
+    while let Some(event) = stream.next().await {
+        if tx.send(LoopEvent::TimeFlip(event)).is_err() {
+            break;
+        }
+    }
+}
+
+/// Send [LoopEvent::Tick] every [TICK_INTERVAL].
+async fn tick(tx: mpsc::UnboundedSender<LoopEvent>) {
+    loop {
+        Delay::new(TICK_INTERVAL).await;
+        if tx.send(LoopEvent::Tick).is_err() {
+            break;
+        }
+    }
+}
+
+/// Send [LoopEvent::AutosaveDue] every [AUTOSAVE_CHECK_INTERVAL].
+async fn autosave_ticker(tx: mpsc::UnboundedSender<LoopEvent>) {
+    loop {
+        Delay::new(AUTOSAVE_CHECK_INTERVAL).await;
+        if tx.send(LoopEvent::AutosaveDue).is_err() {
+            break;
+        }
+    }
+}
+
+/// Watch `path` for on-disk changes, e.g. edits from another tool or a sync from another
+/// machine, sending [LoopEvent::FileChanged] whenever its contents are modified.
+fn watch_persistent_file(
+    path: &Path,
+    tx: mpsc::UnboundedSender<LoopEvent>,
+) -> notify::Result<RecommendedWatcher> {
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<NotifyEvent>| {
+        if let Ok(event) = res {
+            if matches!(event.kind, EventKind::Modify(_)) {
+                let _ = tx.send(LoopEvent::FileChanged);
+            }
+        }
+    })?;
+    watcher.watch(path, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
 async fn read_config(path: impl AsRef<Path>) -> anyhow::Result<Config> {
     let toml = fs::read_to_string(path).await?;
     let config: Config = toml::from_str(&toml)?;
@@ -260,25 +482,136 @@ enum State {
     Selecting,
     Editing,
     Paused,
+    Report,
+    Searching,
+    EditingTime,
 }
 
 impl State {
-    fn get_description(&self) -> String {
+    fn get_description(&self, app: &App) -> String {
         match self {
             Self::Selecting => {
-                String::from("[Up/Down] Move, [->] Edit, [p] Pause, [d] Done, [t] Toggle Visibility, [s] Sync, [q] Quit")
+                String::from("[Up/Down] Move, [->] Edit, [p] Pause, [d] Done, [t] Toggle Visibility, [/] Search, [s] Sync, [r] Report, [q] Quit")
             }
-            Self::Editing => String::from("[Esc] Finish editing"),
+            Self::Editing => String::from(
+                "[Esc] Finish editing, [Ctrl+t] Edit time, [Ctrl+v] Paste, [Ctrl+y] Yank",
+            ),
             Self::Paused => String::from("[p] Unpause"),
+            Self::Report => String::from("[g] Toggle Day/Week, [Esc] Back"),
+            Self::Searching => format!(
+                "Search: {} ({} match{})  [Enter] Accept, [Esc] Cancel",
+                app.query.as_deref().unwrap_or(""),
+                app.items.items.len(),
+                if app.items.items.len() == 1 { "" } else { "es" }
+            ),
+            Self::EditingTime => String::from(
+                "[Left/Right] Move start, [Up/Down] Resize, [x] Split here, [Enter] Save, [Esc] Cancel",
+            ),
         }
     }
 }
 
+/// Granularity a [State::Report] pane groups entries by.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ReportGranularity {
+    Day,
+    Week,
+}
+
+impl ReportGranularity {
+    fn toggle(self) -> Self {
+        match self {
+            Self::Day => Self::Week,
+            Self::Week => Self::Day,
+        }
+    }
+}
+
+/// The label for a report group starting on `date`: the bare date for [ReportGranularity::Day],
+/// or a `start – end` range for [ReportGranularity::Week].
+fn report_group_label(date: NaiveDate, granularity: ReportGranularity) -> String {
+    match granularity {
+        ReportGranularity::Day => date.to_string(),
+        ReportGranularity::Week => {
+            let end = date.checked_add_days(Days::new(6)).expect("in range");
+            format!("{date} – {}", end.format("%m-%d"))
+        }
+    }
+}
+
+/// Group visible `entries` by local calendar day or ISO week (Monday start), summing durations
+/// per facet, the way a timesheet would.
+fn build_report(
+    entries: &HashMap<u32, MyEntry>,
+    granularity: ReportGranularity,
+) -> BTreeMap<NaiveDate, BTreeMap<Facet, Duration>> {
+    let local = Local::now().timezone();
+
+    let mut report = BTreeMap::<NaiveDate, BTreeMap<Facet, Duration>>::new();
+    for entry in entries.values().filter(|e| e.visible) {
+        let date = entry.entry.time.with_timezone(&local).date_naive();
+        let key = match granularity {
+            ReportGranularity::Day => date,
+            ReportGranularity::Week => date
+                .checked_sub_days(Days::new(date.weekday().num_days_from_monday().into()))
+                .expect("in range"),
+        };
+
+        let day = report.entry(key).or_default();
+        let sum = day.entry(entry.entry.facet.clone()).or_default();
+        *sum += entry.entry.duration;
+    }
+    report
+}
+
+/// Render [build_report]'s breakdown as lines of text: a header and per-facet row for each
+/// group, a group total, and a grand total at the bottom.
+fn report_lines(app: &App, config: &Config) -> Vec<String> {
+    let report = build_report(&app.entries, app.report_granularity);
+    let max_len = longest_facet_name(config);
+
+    let mut lines = Vec::new();
+    let mut grand_total = Duration::default();
+    for (date, facets) in &report {
+        lines.push(format!(
+            "== {} ==",
+            report_group_label(*date, app.report_granularity)
+        ));
+
+        let mut day_total = Duration::default();
+        for (facet, duration) in facets {
+            lines.push(format!(
+                "  {:width$}  {}",
+                facet_name(facet, config),
+                DurationView(duration),
+                width = max_len,
+            ));
+            day_total += *duration;
+        }
+        lines.push(format!(
+            "  {:width$}  {}",
+            "Total",
+            DurationView(&day_total),
+            width = max_len,
+        ));
+        lines.push(String::new());
+
+        grand_total += day_total;
+    }
+    lines.push(format!(
+        "{:width$}  {}",
+        "Grand total",
+        DurationView(&grand_total),
+        width = max_len,
+    ));
+    lines
+}
+
 async fn run<B: Backend>(terminal: &mut Terminal<B>, opt: Options) -> anyhow::Result<()> {
     let config = read_config(opt.config).await?;
     let (mut last_seen, entries) = load_history(&opt.persistent_file).await?;
 
-    let (mut bg_task, session) = BluetoothSession::new().await?;
+    let (bg_task, session) = BluetoothSession::new().await?;
     let timeflip = TimeFlip::connect(&session, Some(config.password)).await?;
 
     let mut app = App::new_from_entries(entries);
@@ -303,7 +636,7 @@ async fn run<B: Backend>(terminal: &mut Terminal<B>, opt: Options) -> anyhow::Re
             }
         }
     }
-    app.update_entry_list();
+    app.update_entry_list(&config);
 
     let mut textarea = if let Some(selected) = &app.items.selected() {
         let text = app
@@ -318,155 +651,346 @@ async fn run<B: Backend>(terminal: &mut Terminal<B>, opt: Options) -> anyhow::Re
     };
 
     let mut state = State::Selecting;
-    let mut reader = EventStream::new();
+    // `None` on a headless system (e.g. no X11/Wayland/clipboard manager running), in which case
+    // paste/yank are silently no-ops.
+    let mut clipboard = Clipboard::new().ok();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let _watcher = watch_persistent_file(&opt.persistent_file, tx.clone())?;
 
     timeflip.subscribe_double_tap().await?;
     timeflip.subscribe_facet().await?;
-    let mut stream = timeflip.event_stream().await?;
+    let stream = timeflip.event_stream().await?;
 
-    loop {
-        textarea.set_block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Additional information"),
-        );
-        terminal.draw(|f| ui(f, &mut app, &textarea, &state, &config))?;
+    tokio::spawn(forward_terminal_events(tx.clone()));
+    tokio::spawn(forward_timeflip_events(stream, tx.clone()));
+    tokio::spawn(tick(tx.clone()));
+    tokio::spawn(autosave_ticker(tx.clone()));
+    tokio::spawn(async move {
+        let res = bg_task.await.map_err(anyhow::Error::from);
+        let _ = tx.send(LoopEvent::BgTaskExited(res));
+    });
 
-        let delay = Delay::new(Duration::from_millis(1_000));
-        select! {
-            event = stream.next() => {
-                match event {
-                    Some(TimeEvent::DoubleTap { pause, .. }) => {
-                        match state {
-                            State::Paused => {
-                                if !pause {
-                                    state = State::Selecting;
-                                }
-                            }
-                            _ => {
-                                if pause {
-                                    state = State::Paused;
-                                }
-                            }
-                        }
-                    },
-                    Some(TimeEvent::Facet(_facet)) => {
-                        if matches!(state, State::Paused) {
-                            state = State::Selecting;
-                        }
-                    }
-                    Some(_) => continue,
-                    None => continue,
+    textarea.set_block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Additional information"),
+    );
+    terminal.draw(|f| ui(f, &mut app, &textarea, &state, &config))?;
+
+    while let Some(loop_event) = rx.recv().await {
+        match loop_event {
+            LoopEvent::Tick => {}
+            LoopEvent::BgTaskExited(res) => {
+                if let Err(e) = res {
+                    bail!("bluetooth session background task exited with error: {e}");
                 }
             }
-            _ = delay => { continue; }
-            res = &mut bg_task => {
-                if let Err(e) =res {
-                    bail!("bluetooth session background task exited with error: {e}");
+            LoopEvent::AutosaveDue => {
+                if app.dirty
+                    && app.last_change.elapsed() >= Duration::from_millis(opt.autosave_interval_ms)
+                {
+                    let entries: Vec<MyEntry> = app.entries.values().cloned().collect();
+                    persist_history(&opt.persistent_file, &entries).await?;
+                    app.dirty = false;
+                    app.edited_ids.clear();
                 }
             }
-            maybe_event = reader.next() => {
-                if let Some(Ok(event)) = maybe_event {
-                    match state {
-                        State::Selecting => {
-                        if let Event::Key(key) = event {
-                            if key.kind == KeyEventKind::Press {
-                                match key.code {
-                                    KeyCode::Char('q') => {
-                                        let entries: Vec<MyEntry> = app.entries.into_values().collect();
-                                        persist_history(&opt.persistent_file, &entries).await?;
-                                        return Ok(())
-                                    },
-                                    KeyCode::Char('p') => {
-                                        timeflip.pause().await?;
-                                        state = State::Paused;
-                                    }
-                                    KeyCode::Char('d') => {
-                                        if let Some(selected) = app.items.selected() {
-                                            let entry = app.entries.get_mut(selected).expect("must be present");
-                                            entry.visible = !entry.visible;
-                                            if !entry.visible && !app.show_invisible {
-                                              app.items.remove();
-                                            }
+            LoopEvent::FileChanged => {
+                let (_, on_disk) = load_history(&opt.persistent_file).await?;
+                app.merge_reloaded(on_disk);
+                app.update_entry_list(&config);
+            }
+            LoopEvent::TimeFlip(event) => match event {
+                TimeEvent::DoubleTap { pause, .. } => match state {
+                    State::Paused => {
+                        if !pause {
+                            state = State::Selecting;
+                        }
+                    }
+                    _ => {
+                        if pause {
+                            state = State::Paused;
+                        }
+                    }
+                },
+                TimeEvent::Facet(_facet) => {
+                    if matches!(state, State::Paused) {
+                        state = State::Selecting;
+                    }
+                }
+                _ => {}
+            },
+            LoopEvent::Term(event) => match state {
+                State::Selecting => {
+                    if let Event::Key(key) = event {
+                        if key.kind == KeyEventKind::Press {
+                            match key.code {
+                                KeyCode::Char('q') => {
+                                    let entries: Vec<MyEntry> = app.entries.into_values().collect();
+                                    persist_history(&opt.persistent_file, &entries).await?;
+                                    return Ok(());
+                                }
+                                KeyCode::Char('p') => {
+                                    timeflip.pause().await?;
+                                    state = State::Paused;
+                                }
+                                KeyCode::Char('d') => {
+                                    if let Some(&selected) = app.items.selected() {
+                                        let entry = app
+                                            .entries
+                                            .get_mut(&selected)
+                                            .expect("must be present");
+                                        entry.visible = !entry.visible;
+                                        if !entry.visible && !app.show_invisible {
+                                            app.items.remove();
                                         }
+                                        app.mark_entry_dirty(selected);
                                     }
-                                    KeyCode::Char('t') => {
-                                        app.toggle_visibility();
-                                    }
-                                    KeyCode::Char('s') => {
-                                      let update: Vec<Entry> = timeflip
-                                          .read_history_since(last_seen)
-                                          .await?
-                                          .into_iter()
-                                          .collect();
-                                      for entry in update {
-                                          last_seen = max(entry.id, last_seen);
-                                          match app.entries.entry(entry.id) {
-                                              Vacant(v) => {
-                                                  v.insert(MyEntry {
-                                                      entry,
-                                                      description: vec![],
-                                                      visible: true,
-                                                  });
-                                              }
-                                              Occupied(mut o) => {
-                                                  o.get_mut().entry = entry;
-                                              }
-                                          }
-                                      }
-                                      app.update_entry_list();
+                                }
+                                KeyCode::Char('t') => {
+                                    app.toggle_visibility(&config);
+                                }
+                                KeyCode::Char('r') => {
+                                    state = State::Report;
+                                }
+                                KeyCode::Char('/') => {
+                                    app.query = Some(String::new());
+                                    app.update_entry_list(&config);
+                                    state = State::Searching;
+                                }
+                                KeyCode::Char('s') => {
+                                    let update: Vec<Entry> = timeflip
+                                        .read_history_since(last_seen)
+                                        .await?
+                                        .into_iter()
+                                        .collect();
+                                    if !update.is_empty() {
+                                        app.mark_dirty();
                                     }
-                                    KeyCode::Right => {
-                                        if app.items.selected().is_some() {
-                                            state = State::Editing;
-                                            textarea.set_style(Style::default().fg(Color::White));
+                                    for entry in update {
+                                        last_seen = max(entry.id, last_seen);
+                                        match app.entries.entry(entry.id) {
+                                            Vacant(v) => {
+                                                v.insert(MyEntry {
+                                                    entry,
+                                                    description: vec![],
+                                                    visible: true,
+                                                });
+                                            }
+                                            Occupied(mut o) => {
+                                                o.get_mut().entry = entry;
+                                            }
                                         }
                                     }
-                                    KeyCode::Down => {
-                                        app.items.next();
-                                    },
-                                    KeyCode::Up => {
-                                        app.items.previous();
-                                    },
-                                    _ => {
+                                    app.update_entry_list(&config);
+                                }
+                                KeyCode::Right => {
+                                    if app.items.selected().is_some() {
+                                        state = State::Editing;
+                                        textarea.set_style(Style::default().fg(Color::White));
                                     }
                                 }
-                                let text = if let Some(selected) = app.items.selected() {
-                                    app.entries.get(selected).expect("must be present").description.to_vec()
-                                } else { vec!["".to_string()] };
-                                textarea = TextArea::new(text);
+                                KeyCode::Down => {
+                                    app.items.next();
+                                }
+                                KeyCode::Up => {
+                                    app.items.previous();
+                                }
+                                _ => {}
+                            }
+                            let text = if let Some(selected) = app.items.selected() {
+                                app.entries
+                                    .get(selected)
+                                    .expect("must be present")
+                                    .description
+                                    .to_vec()
+                            } else {
+                                vec!["".to_string()]
+                            };
+                            textarea = TextArea::new(text);
+                        }
+                    }
+                }
+                State::Editing => match event.into() {
+                    Input { key: Key::Esc, .. } => {
+                        state = State::Selecting;
+                        if let Some(&editing_entry) = app.items.selected() {
+                            let entry = app
+                                .entries
+                                .get_mut(&editing_entry)
+                                .expect("must be present");
+                            entry.description = textarea.lines().to_vec();
+                            app.mark_entry_dirty(editing_entry);
+                        }
+                        textarea.set_style(Style::default().fg(Color::Gray));
+                    }
+                    Input {
+                        key: Key::Char('t'),
+                        ctrl: true,
+                        ..
+                    } => {
+                        if let Some(&editing_entry) = app.items.selected() {
+                            let entry = app.entries.get(&editing_entry).expect("must be present");
+                            app.time_edit = Some(TimeEdit {
+                                id: editing_entry,
+                                time: entry.entry.time,
+                                duration: entry.entry.duration,
+                            });
+                            state = State::EditingTime;
+                        }
+                    }
+                    Input {
+                        key: Key::Char('v'),
+                        ctrl: true,
+                        ..
+                    } => {
+                        if let Some(clipboard) = clipboard.as_mut() {
+                            if let Ok(text) = clipboard.get_text() {
+                                textarea.insert_str(&text);
                             }
                         }
-                    },
-                    State::Editing => {
-                        match event.into() {
-                            Input { key: Key::Esc, .. } => {
-                                state = State::Selecting;
-                                if let Some(editing_entry) = app.items.selected() {
-                                    let entry = app.entries.get_mut(editing_entry).expect("must be present");
-                                    entry.description = textarea.lines().to_vec();
+                    }
+                    Input {
+                        key: Key::Char('y'),
+                        ctrl: true,
+                        ..
+                    } => {
+                        if let Some(clipboard) = clipboard.as_mut() {
+                            let _ = clipboard.set_text(textarea.lines().join("\n"));
+                        }
+                    }
+                    input => {
+                        textarea.input(input);
+                    }
+                },
+                State::Paused => match event.into() {
+                    Input {
+                        key: Key::Char('p'),
+                        ..
+                    } => {
+                        timeflip.unpause().await?;
+                        state = State::Selecting;
+                    }
+                    _ => {}
+                },
+                State::Report => {
+                    if let Event::Key(key) = event {
+                        if key.kind == KeyEventKind::Press {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    state = State::Selecting;
                                 }
-                                textarea.set_style(Style::default().fg(Color::Gray));
-                            },
-                            input => {
-                                textarea.input(input);
+                                KeyCode::Char('g') => {
+                                    app.report_granularity = app.report_granularity.toggle();
+                                }
+                                _ => {}
                             }
                         }
                     }
-                    State::Paused => {
-                        match event.into() {
-                                Input { key: Key::Char('p'), .. } => {
-                                    timeflip.unpause().await?;
+                }
+                State::Searching => {
+                    if let Event::Key(key) = event {
+                        if key.kind == KeyEventKind::Press {
+                            match key.code {
+                                KeyCode::Esc => {
+                                    app.query = None;
+                                    app.update_entry_list(&config);
                                     state = State::Selecting;
                                 }
-                                _ => {},
+                                KeyCode::Enter => {
+                                    state = State::Selecting;
+                                }
+                                KeyCode::Backspace => {
+                                    if let Some(query) = app.query.as_mut() {
+                                        query.pop();
+                                    }
+                                    app.update_entry_list(&config);
+                                }
+                                KeyCode::Char(c) => {
+                                    if let Some(query) = app.query.as_mut() {
+                                        query.push(c.to_ascii_lowercase());
+                                    }
+                                    app.update_entry_list(&config);
+                                }
+                                _ => {}
+                            }
                         }
                     }
                 }
-            }
-            }
-        };
+                State::EditingTime => {
+                    if let Event::Key(key) = event {
+                        if key.kind == KeyEventKind::Press {
+                            if let Some(edit) = app.time_edit.as_mut() {
+                                match key.code {
+                                    KeyCode::Esc => {
+                                        app.time_edit = None;
+                                        state = State::Editing;
+                                    }
+                                    KeyCode::Left => {
+                                        edit.time -= chrono::Duration::minutes(1);
+                                    }
+                                    KeyCode::Right => {
+                                        edit.time += chrono::Duration::minutes(1);
+                                    }
+                                    KeyCode::Up => {
+                                        edit.duration += Duration::from_secs(60);
+                                    }
+                                    KeyCode::Down => {
+                                        edit.duration =
+                                            edit.duration.saturating_sub(Duration::from_secs(60));
+                                    }
+                                    KeyCode::Char('x') => {
+                                        let original_duration = app
+                                            .entries
+                                            .get(&edit.id)
+                                            .expect("must be present")
+                                            .entry
+                                            .duration;
+                                        if edit.duration > Duration::ZERO
+                                            && edit.duration < original_duration
+                                        {
+                                            app.split_entry(edit.id, edit.duration);
+                                            app.update_entry_list(&config);
+                                        }
+                                        app.time_edit = None;
+                                        state = State::Editing;
+                                    }
+                                    KeyCode::Enter => {
+                                        let end_time = edit.time
+                                            + chrono::Duration::from_std(edit.duration)
+                                                .expect("should work");
+                                        if !app.overlaps_next(edit.id, end_time) {
+                                            let id = edit.id;
+                                            let time = edit.time;
+                                            let duration = edit.duration;
+                                            let entry =
+                                                app.entries.get_mut(&id).expect("must be present");
+                                            entry.entry.time = time;
+                                            entry.entry.duration = duration;
+                                            app.mark_entry_dirty(id);
+                                            app.time_edit = None;
+                                            state = State::Editing;
+                                        }
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+        }
+
+        textarea.set_block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Additional information"),
+        );
+        terminal.draw(|f| ui(f, &mut app, &textarea, &state, &config))?;
     }
+
+    Ok(())
 }
 
 fn ui<B: Backend>(
@@ -483,16 +1007,31 @@ fn ui<B: Backend>(
     f.render_widget(
         Block::new()
             .borders(Borders::TOP)
-            .title(state.get_description()),
+            .title(state.get_description(app)),
         main_layout[1],
     );
+
+    if matches!(state, State::Report) {
+        let items: Vec<ListItem> = report_lines(app, config)
+            .into_iter()
+            .map(ListItem::new)
+            .collect();
+        let report = List::new(items).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Timesheet Report"),
+        );
+        f.render_widget(report, main_layout[0]);
+        return;
+    }
+
     let inner_layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(main_layout[0]);
     let list_selected_color = match state {
-        State::Selecting => Color::White,
-        State::Editing | State::Paused => Color::Gray,
+        State::Selecting | State::Searching => Color::White,
+        State::Editing | State::Paused | State::Report | State::EditingTime => Color::Gray,
     };
     let max_len = longest_facet_name(config);
     let items: Vec<ListItem> = app